@@ -45,6 +45,10 @@ class CognitiveConfig:
     whisper_model: str = "whisper.cpp/models/for-tests-ggml-tiny.en.bin"
     whisper_executable: str = "whisper.cpp/build/bin/Release/whisper-stream-stdin.exe"
     whisper_threads: int = 4
+    whisper_language: str = "en"
+    whisper_use_gpu: bool = True
+    whisper_gpu_device: Optional[int] = None
+    whisper_flash_attn: bool = False
 
     # Chronicler settings
     context_max_length: int = 50
@@ -194,6 +198,20 @@ class FrontendWebSocketServer:
         if self.clients:
             logger.info(f"🎯 Broadcast to {len(self.clients)} client(s): {text}")
 
+    async def broadcast_transcript(self, text: str):
+        """Broadcast a finalized transcript line to all connected clients"""
+        if self.is_paused or not self.clients:
+            return
+
+        message = {
+            "type": "transcript",
+            "text": text,
+            "timestamp": int(time.time() * 1000)
+        }
+
+        for client in list(self.clients):
+            await self.send_to_client(client, message)
+
     async def broadcast_status(self):
         """Broadcast current system status to all clients"""
         if not self.clients:
@@ -412,9 +430,22 @@ class AudioPipeline:
                 self.config.whisper_executable,
                 "-m", self.config.whisper_model,
                 "-t", str(self.config.whisper_threads),
-                "-l", "en",
+                "-l", self.config.whisper_language,
                 "--no-timestamps"
             ]
+            if not self.config.whisper_use_gpu:
+                whisper_cmd.append("-ng")
+            if self.config.whisper_flash_attn:
+                whisper_cmd.append("-fa")
+
+            # Pin inference to one GPU (ggml's CUDA and Vulkan backends honour these)
+            whisper_env = None
+            if self.config.whisper_gpu_device is not None:
+                whisper_env = dict(os.environ)
+                # Number CUDA devices like nvidia-smi (PCI order), which the app indexes into
+                whisper_env["CUDA_DEVICE_ORDER"] = "PCI_BUS_ID"
+                whisper_env["CUDA_VISIBLE_DEVICES"] = str(self.config.whisper_gpu_device)
+                whisper_env["GGML_VK_VISIBLE_DEVICES"] = str(self.config.whisper_gpu_device)
 
             logger.info(f"🎙️ Starting direct audio pipeline:")
             logger.info(f"  FFmpeg: {' '.join(ffmpeg_cmd)}")
@@ -432,7 +463,8 @@ class AudioPipeline:
                 *whisper_cmd,
                 stdin=self.ffmpeg_proc.stdout,
                 stdout=asyncio.subprocess.PIPE,
-                stderr=asyncio.subprocess.PIPE
+                stderr=asyncio.subprocess.PIPE,
+                env=whisper_env
             )
 
             # Process transcription output
@@ -550,6 +582,7 @@ class NativeCognitiveEngine:
 
         logger.info(f"🎤 Transcript: {text}")
         self.stats["transcripts_processed"] += 1
+        await self.frontend_server.broadcast_transcript(text)
 
         # Add to chronicler for context
         if self.config.chronicler_enabled:
@@ -603,6 +636,16 @@ async def main():
                        help="Audio input device name")
     parser.add_argument("--whisper-model", default="./backend/whisper.cpp/models/for-tests-ggml-tiny.en.bin",
                        help="Path to Whisper model file")
+    parser.add_argument("--language", default="en",
+                       help="Whisper language code, or 'auto' to detect it")
+    parser.add_argument("--whisper-threads", type=int, default=4,
+                       help="CPU threads for Whisper")
+    parser.add_argument("--no-gpu", action="store_true",
+                       help="Run Whisper on the CPU only")
+    parser.add_argument("--gpu-device", type=int, default=None,
+                       help="Index of the GPU to run Whisper on")
+    parser.add_argument("--flash-attn", action="store_true",
+                       help="Enable flash attention in Whisper")
     parser.add_argument("--ollama-host", default="127.0.0.1",
                        help="Ollama server host")
     parser.add_argument("--ollama-port", type=int, default=11434,
@@ -622,7 +665,12 @@ async def main():
         frontend_ws_host=args.frontend_host,
         frontend_ws_port=args.frontend_port,
         audio_device=args.audio_device,
-        whisper_model=args.whisper_model
+        whisper_model=args.whisper_model,
+        whisper_language=args.language,
+        whisper_threads=args.whisper_threads,
+        whisper_use_gpu=not args.no_gpu,
+        whisper_gpu_device=args.gpu_device,
+        whisper_flash_attn=args.flash_attn
     )
 
     # Start native cognitive engine
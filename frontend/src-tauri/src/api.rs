@@ -0,0 +1,166 @@
+//! Local REST API.
+//!
+//! When `settings.api.enabled` is set, an HTTP server on `127.0.0.1:<settings.api.port>` lets
+//! scripts and other local apps drive the backend without the UI. Every request needs
+//! `Authorization: Bearer <token>`, where the token is the `api-token` secret in the OS keychain
+//! (set it with `set_secret`); without a token all requests are refused.
+//!
+//! - `GET /v1/status` - backend health, as `backend_health` returns it
+//! - `POST /v1/start`, `POST /v1/stop` - start or stop the backend
+//! - `POST /v1/pause`, `POST /v1/resume` - pause or resume transcription; 503 if the backend
+//!   can't be reached
+
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use subtle::ConstantTimeEq;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::config::{ApiOptions, SettingsStore};
+use crate::server_manager::{self, BackendHealth, ServerManager};
+use crate::{power, secrets};
+
+/// Keychain name of the bearer token.
+pub const TOKEN_SECRET: &str = "api-token";
+
+/// The running server, if any: a shutdown trigger and the task serving it.
+#[derive(Default)]
+pub struct ApiServer {
+    running: Mutex<Option<(oneshot::Sender<()>, JoinHandle<()>)>>,
+}
+
+/// Start, stop or rebind the server to match `options`.
+pub fn apply(app: &AppHandle, options: &ApiOptions) {
+    let server = app.state::<ApiServer>();
+    let mut running = server.running.lock().unwrap();
+    let previous = running.take().map(|(shutdown, task)| {
+        let _ = shutdown.send(());
+        task
+    });
+    if !options.enabled {
+        return;
+    }
+
+    let (shutdown, stopped) = oneshot::channel();
+    let app = app.clone();
+    let port = options.port;
+    let task = tauri::async_runtime::spawn(async move {
+        // Let the old server release the port before binding it again.
+        if let Some(previous) = previous {
+            let _ = previous.await;
+        }
+        if let Err(e) = serve(app, port, stopped).await {
+            tracing::error!("REST API on port {port} failed: {e}");
+        }
+    });
+    *running = Some((shutdown, task));
+}
+
+async fn serve(app: AppHandle, port: u16, stopped: oneshot::Receiver<()>) -> std::io::Result<()> {
+    let router = Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/start", post(start))
+        .route("/v1/stop", post(stop))
+        .route("/v1/pause", post(pause))
+        .route("/v1/resume", post(resume))
+        .layer(middleware::from_fn_with_state(app.clone(), authorize))
+        .with_state(app);
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+    tracing::info!("REST API listening on 127.0.0.1:{port}");
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = stopped.await;
+        })
+        .await?;
+    tracing::info!("REST API on port {port} stopped");
+    Ok(())
+}
+
+async fn authorize(State(app): State<AppHandle>, request: Request, next: Next) -> Response {
+    // The keychain lookup blocks (Secret Service goes over D-Bus), so keep it off the runtime.
+    let lookup = tauri::async_runtime::spawn_blocking(move || secrets::get(&app, TOKEN_SECRET))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|token| token);
+    let token = match lookup {
+        Ok(Some(token)) => token,
+        Ok(None) => {
+            return (StatusCode::FORBIDDEN, "no API token configured").into_response();
+        }
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Constant-time, so response timing doesn't reveal how much of a guess was right.
+    let matches =
+        presented.is_some_and(|presented| bool::from(presented.as_bytes().ct_eq(token.as_bytes())));
+    if !matches {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    next.run(request).await
+}
+
+async fn status(State(app): State<AppHandle>) -> Result<Json<BackendHealth>, Response> {
+    blocking(move || app.state::<ServerManager>().health())
+        .await
+        .map(Json)
+}
+
+async fn start(State(app): State<AppHandle>) -> Result<Json<BackendHealth>, Response> {
+    blocking(move || {
+        let args = power::backend_args(&app, &app.state::<SettingsStore>().get());
+        let manager = app.state::<ServerManager>();
+        manager.start(args);
+        manager.health()
+    })
+    .await
+    .map(Json)
+}
+
+async fn stop(State(app): State<AppHandle>) -> Result<Json<BackendHealth>, Response> {
+    blocking(move || {
+        let manager = app.state::<ServerManager>();
+        manager.stop();
+        manager.health()
+    })
+    .await
+    .map(Json)
+}
+
+async fn pause() -> Result<StatusCode, Response> {
+    set_paused(true).await
+}
+
+async fn resume() -> Result<StatusCode, Response> {
+    set_paused(false).await
+}
+
+async fn set_paused(paused: bool) -> Result<StatusCode, Response> {
+    blocking(move || server_manager::set_paused(paused))
+        .await?
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e).into_response())
+}
+
+/// Run supervisor or backend work on the blocking pool; stopping alone can take seconds.
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, Response> {
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())
+}
@@ -0,0 +1,438 @@
+//! Persistent user settings.
+//!
+//! Settings live in `settings.json` inside the app config dir. Every change made through
+//! `set_settings` is written back to disk, broadcast as a `settings-changed` event and applied:
+//! backend options by restarting the supervised backend, hotkeys by re-registering them, the
+//! REST API by rebinding it.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::server_manager::{BackendState, ServerManager};
+use crate::{api, hotkeys, models, power, setup};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Language codes whisper.cpp knows, in its own order.
+const WHISPER_LANGUAGES: &[&str] = &[
+    "en", "zh", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl", "ca", "nl", "ar", "sv", "it",
+    "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs", "ro", "da", "hu", "ta", "no", "th", "ur",
+    "hr", "bg", "lt", "la", "mi", "ml", "cy", "sk", "te", "fa", "lv", "bn", "sr", "az", "sl", "kn",
+    "et", "mk", "br", "eu", "is", "hy", "ne", "mn", "bs", "kk", "sq", "sw", "gl", "mr", "pa", "si",
+    "km", "sn", "yo", "so", "af", "oc", "ka", "be", "tg", "sd", "gu", "am", "yi", "lo", "uz", "fo",
+    "ht", "ps", "tk", "nn", "mt", "sa", "lb", "my", "bo", "tl", "mg", "as", "tt", "haw", "ln",
+    "ha", "ba", "jw", "su", "yue",
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// ggml model handed to the backend; `None` keeps the backend's own default.
+    pub whisper_model: Option<PathBuf>,
+    /// Keep `whisper_model` on the largest downloaded model this machine runs in real time.
+    /// Choosing a model through `set_settings` turns this off, and a model outside the managed
+    /// models dir always wins over the automatic choice.
+    pub auto_model: bool,
+    /// Whisper language code (`en`, `de`, ...) or `auto`.
+    pub language: String,
+    /// Capture device name as ffmpeg's dshow input knows it; `None` keeps the backend default.
+    pub audio_device: Option<String>,
+    pub compute: ComputeOptions,
+    pub power: PowerOptions,
+    pub hotkeys: Hotkeys,
+    /// What to do when a conferencing app starts.
+    pub meeting_apps: Vec<MeetingRule>,
+    /// Set once the first-run setup wizard has finished.
+    pub setup_complete: bool,
+    pub api: ApiOptions,
+}
+
+/// How whisper runs inference.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComputeOptions {
+    /// CPU threads; `None` keeps the backend default.
+    pub threads: Option<u32>,
+    pub use_gpu: bool,
+    /// GPU to pin inference to (CUDA/Vulkan device index); `None` lets whisper pick.
+    pub gpu_device: Option<u32>,
+    pub flash_attn: bool,
+}
+
+impl Default for ComputeOptions {
+    fn default() -> Self {
+        Self {
+            threads: None,
+            use_gpu: true,
+            gpu_device: None,
+            flash_attn: false,
+        }
+    }
+}
+
+/// How inference is throttled on battery or in power-saver mode.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerOptions {
+    pub throttle_on_battery: bool,
+    /// Catalog model to switch to while throttled, if downloaded; `None` keeps the model.
+    pub battery_model: Option<String>,
+    /// CPU threads while throttled; `None` halves the usual thread count.
+    pub battery_threads: Option<u32>,
+}
+
+impl Default for PowerOptions {
+    fn default() -> Self {
+        Self {
+            throttle_on_battery: true,
+            battery_model: None,
+            battery_threads: None,
+        }
+    }
+}
+
+/// The localhost REST API for scripts and other local apps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiOptions {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9083,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MeetingRule {
+    /// Process name as the OS reports it, e.g. `Zoom.exe`; matched case-insensitively.
+    pub process: String,
+    pub action: MeetingAction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeetingAction {
+    Ignore,
+    /// Emit `meeting-detected` so the UI can offer to start transcribing.
+    Notify,
+    /// Start the backend right away. Needs microphone activity detection (Windows only);
+    /// elsewhere it behaves like `Notify`.
+    AutoStart,
+}
+
+impl MeetingRule {
+    fn notify(process: &str) -> Self {
+        Self {
+            process: process.to_string(),
+            action: MeetingAction::Notify,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hotkeys {
+    /// Accelerator string such as `CmdOrCtrl+Shift+Space`.
+    pub push_to_talk: Option<String>,
+    /// Accelerator that pauses transcription, or resumes it when paused.
+    pub toggle_pause: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            whisper_model: None,
+            auto_model: true,
+            language: "en".to_string(),
+            audio_device: None,
+            compute: ComputeOptions::default(),
+            power: PowerOptions::default(),
+            hotkeys: Hotkeys::default(),
+            meeting_apps: vec![
+                MeetingRule::notify("Zoom.exe"),
+                MeetingRule::notify("zoom.us"),
+                MeetingRule::notify("ms-teams.exe"),
+                MeetingRule::notify("Teams.exe"),
+                MeetingRule::notify("Webex.exe"),
+            ],
+            setup_complete: false,
+            api: ApiOptions::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Command line arguments for `brain_native.py` derived from these settings.
+    pub fn backend_args(&self) -> Vec<String> {
+        let mut args = vec!["--language".to_string(), self.language.clone()];
+        if let Some(model) = &self.whisper_model {
+            args.push("--whisper-model".to_string());
+            args.push(model.to_string_lossy().into_owned());
+        }
+        if let Some(device) = &self.audio_device {
+            args.push("--audio-device".to_string());
+            args.push(device.clone());
+        }
+        if let Some(threads) = self.compute.threads {
+            args.push("--whisper-threads".to_string());
+            args.push(threads.to_string());
+        }
+        if !self.compute.use_gpu {
+            args.push("--no-gpu".to_string());
+        }
+        if let Some(device) = self.compute.gpu_device {
+            args.push("--gpu-device".to_string());
+            args.push(device.to_string());
+        }
+        if self.compute.flash_attn {
+            args.push("--flash-attn".to_string());
+        }
+        args
+    }
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Load settings from the app config dir, falling back to defaults if the file is missing
+    /// or unreadable.
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let path = app
+            .path()
+            .app_config_dir()
+            .map_err(|e| e.to_string())?
+            .join(SETTINGS_FILE);
+
+        let settings = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                tracing::warn!("ignoring invalid {}: {e}", path.display());
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        };
+
+        Ok(Self {
+            path,
+            settings: Mutex::new(settings),
+        })
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Replace the settings and persist them, returning the previous value.
+    pub fn set(&self, settings: Settings) -> Result<Settings, String> {
+        let contents = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+
+        // Held across the write so concurrent writers neither share the temp file nor leave
+        // the file and the in-memory settings disagreeing.
+        let mut current = self.settings.lock().unwrap();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        // Write next to the real file and rename so a crash never leaves half a file behind.
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &self.path).map_err(|e| e.to_string())?;
+
+        Ok(std::mem::replace(&mut *current, settings))
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(store: State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+#[tauri::command]
+pub async fn set_settings(app: AppHandle, mut settings: Settings) -> Result<Settings, String> {
+    update_from_current(app, move |current| {
+        // A model picked by hand is an override; don't swap it out at the next start.
+        if settings.whisper_model != current.whisper_model
+            && settings.auto_model == current.auto_model
+        {
+            settings.auto_model = false;
+        }
+        settings
+    })
+    .await
+}
+
+/// Lower-case `code` and check that whisper knows it (or that it is `auto`); the backend would
+/// only fail on an unknown code once it starts whisper.
+fn normalize_language(code: &str) -> Result<String, String> {
+    let code = code.trim().to_ascii_lowercase();
+    if code != "auto" && !WHISPER_LANGUAGES.contains(&code.as_str()) {
+        return Err(format!("'{code}' is not a language whisper supports"));
+    }
+    Ok(code)
+}
+
+/// Set the whisper language (`en`, `es`, ...), or `auto` to let whisper detect it per window.
+#[tauri::command]
+pub async fn set_language(app: AppHandle, code: String) -> Result<Settings, String> {
+    let language = normalize_language(&code)?;
+    update_from_current(app, move |mut settings| {
+        settings.language = language;
+        settings
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_compute_options(
+    app: AppHandle,
+    options: ComputeOptions,
+) -> Result<Settings, String> {
+    if options.threads == Some(0) {
+        return Err("threads must be at least 1".to_string());
+    }
+
+    update_from_current(app, move |mut settings| {
+        settings.compute = options;
+        settings
+    })
+    .await
+}
+
+/// `update` with settings derived from the current ones, on the blocking pool: restarting the
+/// backend waits for the old one to shut down, which would freeze the UI on the main thread.
+async fn update_from_current(
+    app: AppHandle,
+    change: impl FnOnce(Settings) -> Settings + Send + 'static,
+) -> Result<Settings, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let settings = change(store.get());
+        update(&app, &store, &app.state::<ServerManager>(), settings)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Persist `settings` and apply whatever changed. New hotkeys are registered first, so a combo
+/// that is invalid or taken leaves both the saved settings and the working shortcut untouched.
+///
+/// A new language re-runs automatic model selection, and a language other than English is
+/// refused while the model is English-only: whisper would silently keep transcribing English.
+pub fn update(
+    app: &AppHandle,
+    store: &SettingsStore,
+    manager: &ServerManager,
+    mut settings: Settings,
+) -> Result<Settings, String> {
+    let current = store.get();
+    if settings.language != current.language && settings.auto_model {
+        setup::select_model(app, &mut settings)?;
+    }
+    let model_changed = settings.whisper_model != current.whisper_model;
+    if (model_changed || settings.language != current.language) && settings.language != "en" {
+        if let Some(model) = settings
+            .whisper_model
+            .as_deref()
+            .filter(|model| models::english_only(model))
+        {
+            return Err(format!(
+                "{} only transcribes English; download or choose a multilingual model for '{}'",
+                model.display(),
+                settings.language
+            ));
+        }
+    }
+
+    let hotkeys_changed = current.hotkeys != settings.hotkeys;
+    if hotkeys_changed {
+        hotkeys::validate(&settings.hotkeys)?;
+        if let Err(e) = hotkeys::apply(app, &settings.hotkeys) {
+            restore_hotkeys(app, &current.hotkeys);
+            return Err(e);
+        }
+    }
+
+    let previous = match store.set(settings.clone()) {
+        Ok(previous) => previous,
+        Err(e) => {
+            if hotkeys_changed {
+                restore_hotkeys(app, &current.hotkeys);
+            }
+            return Err(e);
+        }
+    };
+
+    // A backend the user stopped stays stopped; the next start picks up the new arguments.
+    let args = power::backend_args(app, &settings);
+    if power::backend_args(app, &previous) != args && manager.state() != BackendState::Stopped {
+        manager.restart(args);
+    }
+    if previous.api != settings.api {
+        api::apply(app, &settings.api);
+    }
+    let _ = app.emit("settings-changed", &settings);
+
+    Ok(settings)
+}
+
+fn restore_hotkeys(app: &AppHandle, hotkeys: &Hotkeys) {
+    if let Err(e) = hotkeys::apply(app, hotkeys) {
+        tracing::warn!("could not restore global shortcuts: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args_with(change: impl FnOnce(&mut Settings)) -> Vec<String> {
+        let mut settings = Settings::default();
+        change(&mut settings);
+        settings.backend_args()
+    }
+
+    #[test]
+    fn default_settings_only_pass_the_language() {
+        assert_eq!(Settings::default().backend_args(), ["--language", "en"]);
+    }
+
+    #[test]
+    fn each_option_maps_to_its_flag() {
+        assert!(
+            args_with(|s| s.whisper_model = Some(PathBuf::from("m.bin")))
+                .ends_with(&["--whisper-model".to_string(), "m.bin".to_string()])
+        );
+        assert!(args_with(|s| s.audio_device = Some("Mic".to_string()))
+            .ends_with(&["--audio-device".to_string(), "Mic".to_string()]));
+        assert!(args_with(|s| s.compute.threads = Some(6))
+            .ends_with(&["--whisper-threads".to_string(), "6".to_string()]));
+        assert!(args_with(|s| s.compute.use_gpu = false).ends_with(&["--no-gpu".to_string()]));
+        assert!(args_with(|s| s.compute.gpu_device = Some(1))
+            .ends_with(&["--gpu-device".to_string(), "1".to_string()]));
+        assert!(args_with(|s| s.compute.flash_attn = true).ends_with(&["--flash-attn".to_string()]));
+    }
+
+    #[test]
+    fn language_codes_are_normalized_and_validated() {
+        assert_eq!(normalize_language(" EN ").unwrap(), "en");
+        assert_eq!(normalize_language("haw").unwrap(), "haw");
+        assert_eq!(normalize_language("Auto").unwrap(), "auto");
+        assert_eq!(normalize_language("yue").unwrap(), "yue");
+        assert!(normalize_language("e").is_err());
+        assert!(normalize_language("xx").is_err());
+        assert!(normalize_language("eng").is_err());
+        assert!(normalize_language("deu").is_err());
+        assert!(normalize_language("").is_err());
+    }
+}
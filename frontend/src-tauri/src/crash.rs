@@ -0,0 +1,149 @@
+//! Crash reports.
+//!
+//! A panic hook writes a plain-text report - panic message and location, a backtrace, the
+//! backend supervisor state and the last log lines - to `crashes/` in the app log dir before
+//! the default hook runs. `list_crash_reports` returns the reports on disk, for attaching to an
+//! issue, and `delete_crash_reports` removes them.
+
+use std::backtrace::Backtrace;
+use std::fmt::{Display, Write as _};
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::logging::{Logging, RecentLogs};
+use crate::server_manager::ServerManager;
+
+/// How many recent log lines go into a report.
+const LOG_LINES: usize = 200;
+
+#[derive(Serialize)]
+pub struct CrashReport {
+    pub file_name: String,
+    /// Unix timestamp (seconds) of the crash.
+    pub created: u64,
+    pub contents: String,
+}
+
+fn crash_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_log_dir()
+        .map(|dir| dir.join("crashes"))
+        .map_err(|e| e.to_string())
+}
+
+/// Install the panic hook. Call after logging is set up so reports include recent log lines.
+pub fn install(app: &AppHandle) -> Result<(), String> {
+    let dir = crash_dir(app)?;
+    let version = app.package_info().version.to_string();
+    let logs = app.state::<Logging>().recent();
+    let manager = app.state::<ServerManager>().inner().clone();
+
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = render(&info, &version, &manager, &logs);
+        let created = unix_now();
+        let path = dir.join(format!("crash-{created}.txt"));
+        // Straight to stderr: tracing would go through the recent-logs buffer, whose lock the
+        // panicking thread may hold.
+        match fs::create_dir_all(&dir).and_then(|()| fs::write(&path, report)) {
+            Ok(()) => eprintln!("crash report written to {}", path.display()),
+            Err(e) => eprintln!("could not write crash report: {e}"),
+        }
+        // A panic on the main thread ends the app without `RunEvent::Exit`, and the backend
+        // runs in a process group of its own on Unix.
+        #[cfg(unix)]
+        if std::thread::current().name() == Some("main") {
+            crate::server_manager::kill_backend_group();
+        }
+        default_hook(info);
+    }));
+
+    Ok(())
+}
+
+fn render(info: &dyn Display, version: &str, manager: &ServerManager, logs: &RecentLogs) -> String {
+    let mut report = String::new();
+    let thread = std::thread::current();
+
+    let _ = writeln!(report, "earshot-copilot {version} crashed");
+    let _ = writeln!(
+        report,
+        "os: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(report, "thread: {}", thread.name().unwrap_or("<unnamed>"));
+    let _ = writeln!(report, "panic: {info}");
+
+    // Never block here: the panic may have happened while the supervisor held its lock.
+    let _ = writeln!(report, "\n== backend ==");
+    match manager.try_health() {
+        Some(health) => {
+            let _ = writeln!(
+                report,
+                "state: {:?}, pid: {:?}, restarts: {}, reachable: {}, last error: {:?}",
+                health.state, health.pid, health.restarts, health.reachable, health.last_error
+            );
+        }
+        None => {
+            let _ = writeln!(report, "<supervisor state locked>");
+        }
+    }
+
+    let _ = writeln!(report, "\n== backtrace ==\n{}", Backtrace::force_capture());
+
+    let _ = writeln!(report, "== last {LOG_LINES} log lines ==");
+    for line in logs.try_last(LOG_LINES) {
+        let _ = writeln!(report, "{line}");
+    }
+
+    report
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    let Ok(entries) = fs::read_dir(crash_dir(&app)?) else {
+        return Ok(Vec::new());
+    };
+
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let created = file_name
+                .strip_prefix("crash-")?
+                .strip_suffix(".txt")?
+                .parse()
+                .ok()?;
+            let contents = fs::read_to_string(entry.path()).ok()?;
+            Some(CrashReport {
+                file_name,
+                created,
+                contents,
+            })
+        })
+        .collect();
+    reports.sort_by_key(|report| report.created);
+
+    Ok(reports)
+}
+
+#[tauri::command]
+pub fn delete_crash_reports(app: AppHandle) -> Result<(), String> {
+    match fs::remove_dir_all(crash_dir(&app)?) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
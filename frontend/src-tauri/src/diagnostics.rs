@@ -0,0 +1,189 @@
+//! Self-test for bug reports.
+//!
+//! `run_diagnostics` checks everything the transcription stack depends on - the Python backend
+//! and its tools, the configured model, the backend WebSocket (including a ping round trip) and
+//! Ollama - and returns a structured report the UI can show or copy into an issue.
+
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::config::SettingsStore;
+use crate::server_manager::{self, ServerManager};
+
+const OLLAMA_ADDR: ([u8; 4], u16) = ([127, 0, 0, 1], 11434);
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(2);
+/// The model brain_native.py uses when none is configured, relative to the project root.
+const DEFAULT_MODEL: &str = "backend/whisper.cpp/models/for-tests-ggml-tiny.en.bin";
+/// The whisper-stream-stdin binary brain_native.py launches (its `whisper_executable`), relative
+/// to the directory the backend runs in, which is the project root.
+const WHISPER_EXECUTABLE: &str = "whisper.cpp/build/bin/Release/whisper-stream-stdin.exe";
+
+#[derive(Serialize)]
+pub struct DiagnosticsReport {
+    pub app_version: String,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub checks: Vec<Check>,
+}
+
+#[derive(Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+impl Check {
+    fn run(name: &'static str, check: impl FnOnce() -> Result<String, String>) -> Self {
+        let started = Instant::now();
+        let (ok, detail) = match check() {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Check {
+            name,
+            ok,
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn run_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, String> {
+    tauri::async_runtime::spawn_blocking(move || collect(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn collect(app: &AppHandle) -> DiagnosticsReport {
+    let settings = app.state::<SettingsStore>().get();
+    let health = app.state::<ServerManager>().health();
+
+    let checks = vec![
+        Check::run("project_root", || {
+            server_manager::project_root()
+                .map(|root| root.display().to_string())
+                .ok_or_else(|| "backend/brain_native.py not found".to_string())
+        }),
+        Check::run("python", || {
+            tool_version(&server_manager::python_executable(), "--version")
+        }),
+        Check::run("ffmpeg", || tool_version("ffmpeg", "-version")),
+        Check::run("whisper_executable", whisper_executable_status),
+        Check::run("whisper_model", || {
+            let model = settings
+                .whisper_model
+                .clone()
+                .or_else(|| server_manager::project_root().map(|root| root.join(DEFAULT_MODEL)));
+            model_status(model)
+        }),
+        Check::run("backend_process", || {
+            let summary = format!(
+                "{:?}, pid {:?}, {} restarts",
+                health.state, health.pid, health.restarts
+            );
+            match &health.last_error {
+                Some(error) if !health.reachable => Err(format!("{summary}: {error}")),
+                _ => Ok(summary),
+            }
+        }),
+        Check::run("backend_websocket", backend_ping),
+        Check::run("ollama", || {
+            TcpStream::connect_timeout(&SocketAddr::from(OLLAMA_ADDR), NETWORK_TIMEOUT)
+                .map(|_| format!("listening on {OLLAMA_ADDR:?}"))
+                .map_err(|e| e.to_string())
+        }),
+    ];
+
+    DiagnosticsReport {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        checks,
+    }
+}
+
+fn tool_version(program: &str, flag: &str) -> Result<String, String> {
+    let output = Command::new(program)
+        .arg(flag)
+        .output()
+        .map_err(|e| format!("{program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("{program} {flag} exited with {}", output.status));
+    }
+
+    // python prints its version on stderr on old releases; ffmpeg on stdout.
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    Ok(String::from_utf8_lossy(&text)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string())
+}
+
+fn whisper_executable_status() -> Result<String, String> {
+    let root = server_manager::project_root().ok_or("project root not found")?;
+    let path = root.join(WHISPER_EXECUTABLE);
+    if path.is_file() {
+        return Ok(path.display().to_string());
+    }
+
+    // The backend README builds it under backend/, which the relative path misses when the
+    // backend runs from the project root.
+    let misplaced = root.join("backend").join(WHISPER_EXECUTABLE);
+    if misplaced.is_file() {
+        return Err(format!(
+            "{} not found; there is one at {}",
+            path.display(),
+            misplaced.display()
+        ));
+    }
+    Err(format!("{} not found", path.display()))
+}
+
+fn model_status(model: Option<PathBuf>) -> Result<String, String> {
+    let model = model.ok_or("no model configured and project root not found")?;
+    let metadata = std::fs::metadata(&model).map_err(|e| format!("{}: {e}", model.display()))?;
+    Ok(format!(
+        "{} ({} MB)",
+        model.display(),
+        metadata.len() / 1_000_000
+    ))
+}
+
+/// Connect to the backend WebSocket and time a ping/pong round trip.
+fn backend_ping() -> Result<String, String> {
+    let mut socket = server_manager::connect_websocket(NETWORK_TIMEOUT)?;
+
+    let started = Instant::now();
+    server_manager::send_json(&mut socket, serde_json::json!({ "type": "ping" }))?;
+    // The backend greets new clients with a status message first; skip until the pong.
+    let result = loop {
+        match server_manager::read_json(&mut socket) {
+            Ok(Some(reply)) if reply["type"] == "pong" => {
+                break Ok(format!(
+                    "ping round trip {} ms",
+                    started.elapsed().as_millis()
+                ))
+            }
+            Err(e) => break Err(e),
+            _ if started.elapsed() > NETWORK_TIMEOUT => {
+                break Err("no pong from backend".to_string())
+            }
+            _ => {}
+        }
+    };
+    let _ = socket.close(None);
+    result
+}
@@ -0,0 +1,73 @@
+//! Global shortcuts.
+//!
+//! The push-to-talk combo works while any app has focus: pressing it emits a `push-to-talk`
+//! event with `active: true` and releasing it emits `active: false`. The HUD turns those into
+//! resume/pause messages for the backend. The toggle-pause combo pauses or resumes the backend
+//! directly, so it works without the HUD.
+
+use std::thread;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::config::Hotkeys;
+use crate::server_manager;
+
+#[derive(Clone, Serialize)]
+struct PushToTalk {
+    active: bool,
+}
+
+/// Check that every combo in `hotkeys` parses, without touching what is registered.
+pub fn validate(hotkeys: &Hotkeys) -> Result<(), String> {
+    parse("push-to-talk", hotkeys.push_to_talk.as_deref())?;
+    parse("toggle-pause", hotkeys.toggle_pause.as_deref())?;
+    Ok(())
+}
+
+fn parse(name: &str, combo: Option<&str>) -> Result<Option<Shortcut>, String> {
+    combo
+        .map(|combo| {
+            combo
+                .parse()
+                .map_err(|e| format!("invalid {name} shortcut '{combo}': {e}"))
+        })
+        .transpose()
+}
+
+/// (Re-)register all global shortcuts from `hotkeys`, dropping any registered before. Nothing
+/// is unregistered if a combo doesn't parse; if registering fails (say, another app owns the
+/// combo) the caller should re-apply the previous hotkeys.
+pub fn apply(app: &AppHandle, hotkeys: &Hotkeys) -> Result<(), String> {
+    let push_to_talk = parse("push-to-talk", hotkeys.push_to_talk.as_deref())?;
+    let toggle_pause = parse("toggle-pause", hotkeys.toggle_pause.as_deref())?;
+
+    let shortcuts = app.global_shortcut();
+    shortcuts.unregister_all().map_err(|e| e.to_string())?;
+
+    if let Some(shortcut) = push_to_talk {
+        shortcuts
+            .on_shortcut(shortcut, |app, _, event| {
+                let active = event.state() == ShortcutState::Pressed;
+                let _ = app.emit("push-to-talk", PushToTalk { active });
+            })
+            .map_err(|e| format!("could not register push-to-talk shortcut: {e}"))?;
+    }
+    if let Some(shortcut) = toggle_pause {
+        shortcuts
+            .on_shortcut(shortcut, |_, _, event| {
+                if event.state() != ShortcutState::Pressed {
+                    return;
+                }
+                // Talks to the backend; keep the main thread free.
+                thread::spawn(|| match server_manager::toggle_paused() {
+                    Ok(paused) => tracing::info!("transcription paused: {paused}"),
+                    Err(e) => tracing::warn!("could not toggle pause: {e}"),
+                });
+            })
+            .map_err(|e| format!("could not register toggle-pause shortcut: {e}"))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! Logging.
+//!
+//! Everything goes through `tracing` (records from crates using `log` are bridged in) and is
+//! written to daily-rotated files in the app log dir, to stderr, and to an in-memory buffer of
+//! recent lines for the in-app log viewer. The level filter uses `RUST_LOG` syntax
+//! (`info,earshot_copilot::server_manager=debug`) and can be changed at runtime.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Manager, State};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const DEFAULT_FILTER: &str = "info";
+const MAX_LOG_FILES: usize = 7;
+const RECENT_LINES: usize = 1000;
+
+pub struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    recent: RecentLogs,
+    /// Flushes the file writer when dropped, so it has to live as long as the app.
+    _guard: WorkerGuard,
+}
+
+/// Install the global subscriber. The returned handle should be managed by the app.
+pub fn init(app: &AppHandle) -> Result<Logging, String> {
+    let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    let files = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("earshot")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| e.to_string())?;
+    let (files, guard) = tracing_appender::non_blocking(files);
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+    let recent = RecentLogs::default();
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(io::stderr))
+        .with(fmt::layer().with_ansi(false).with_writer(files))
+        .with(fmt::layer().with_ansi(false).with_writer(recent.clone()))
+        .try_init()
+        .map_err(|e| e.to_string())?;
+
+    Ok(Logging {
+        filter: handle,
+        recent,
+        _guard: guard,
+    })
+}
+
+impl Logging {
+    pub fn recent(&self) -> RecentLogs {
+        self.recent.clone()
+    }
+}
+
+/// Ring buffer holding the most recent formatted log lines.
+#[derive(Clone, Default)]
+pub struct RecentLogs {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl RecentLogs {
+    pub fn last(&self, limit: usize) -> Vec<String> {
+        tail(&self.lines.lock().unwrap(), limit)
+    }
+
+    /// Like `last`, but returns nothing instead of blocking if a writer holds the buffer.
+    pub fn try_last(&self, limit: usize) -> Vec<String> {
+        self.lines
+            .try_lock()
+            .map(|lines| tail(&lines, limit))
+            .unwrap_or_default()
+    }
+}
+
+fn tail(lines: &VecDeque<String>, limit: usize) -> Vec<String> {
+    lines
+        .iter()
+        .skip(lines.len().saturating_sub(limit))
+        .cloned()
+        .collect()
+}
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentLogWriter {
+            lines: self.lines.clone(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Collects one formatted event and pushes it to the buffer when dropped.
+pub struct RecentLogWriter {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    buf: Vec<u8>,
+}
+
+impl Write for RecentLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RecentLogWriter {
+    fn drop(&mut self) {
+        let line = String::from_utf8_lossy(&self.buf).trim_end().to_string();
+        if line.is_empty() {
+            return;
+        }
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == RECENT_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+/// Replace the level filter, e.g. `warn,earshot_copilot=debug`.
+#[tauri::command]
+pub fn set_log_filter(logging: State<'_, Logging>, filter: String) -> Result<(), String> {
+    let filter = EnvFilter::try_new(&filter).map_err(|e| e.to_string())?;
+    logging.filter.reload(filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_recent_logs(logging: State<'_, Logging>, limit: Option<usize>) -> Vec<String> {
+    logging.recent.last(limit.unwrap_or(RECENT_LINES))
+}
@@ -1,10 +1,100 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api;
+mod config;
+mod crash;
+mod diagnostics;
+mod hotkeys;
+mod logging;
+mod meetings;
+mod models;
+mod power;
+mod secrets;
+mod server_manager;
+mod setup;
+mod tray;
+mod tts;
+
+use tauri::{Manager, RunEvent, WindowEvent};
+
+use config::SettingsStore;
+use server_manager::ServerManager;
+
 fn main() {
-    // This is it. This is the entire file's logic.
-    // It just builds the window manager and runs it.
-    tauri::Builder::default()
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+    // Builds the window manager, registers the commands the frontend can invoke and runs it.
+    let app = tauri::Builder::default()
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .manage(ServerManager::new())
+        .manage(tts::Speech::default())
+        .manage(api::ApiServer::default())
+        .manage(models::Downloads::default())
+        .manage(power::Power::new())
+        .setup(|app| {
+            app.manage(logging::init(app.handle())?);
+            crash::install(app.handle())?;
+            #[cfg(unix)]
+            server_manager::install_exit_guard();
+
+            let settings = SettingsStore::load(app.handle())?;
+            setup::select_model_on_start(app.handle(), &settings);
+            if let Err(e) = hotkeys::apply(app.handle(), &settings.get().hotkeys) {
+                tracing::warn!("could not register global shortcuts: {e}");
+            }
+            app.state::<ServerManager>()
+                .start(power::backend_args(app.handle(), &settings.get()));
+            let api_options = settings.get().api;
+            app.manage(settings);
+            api::apply(app.handle(), &api_options);
+            tray::create(app.handle())?;
+            meetings::watch(app.handle().clone());
+            power::watch(app.handle().clone());
+            power::watch_resume(app.handle().clone());
+            Ok(())
+        })
+        // Closing the control window only hides it; the tray keeps the app alive.
+        .on_window_event(|window, event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main" {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            config::get_settings,
+            config::set_settings,
+            config::set_language,
+            config::set_compute_options,
+            logging::set_log_filter,
+            logging::get_recent_logs,
+            diagnostics::run_diagnostics,
+            secrets::set_secret,
+            secrets::clear_secret,
+            secrets::has_secret,
+            crash::list_crash_reports,
+            crash::delete_crash_reports,
+            models::list_models,
+            models::download_model,
+            power::get_power_state,
+            server_manager::backend_health,
+            server_manager::restart_backend,
+            server_manager::pause_transcription,
+            server_manager::resume_transcription,
+            setup::probe_hardware,
+            setup::recommend_model,
+            setup::reevaluate_model,
+            setup::complete_setup,
+            setup::mic_test,
+            tts::speak_selection,
+            tts::stop_speaking,
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application");
+
+    app.run(|app, event| {
+        if let RunEvent::Exit = event {
+            app.state::<ServerManager>().stop();
+        }
+    });
 }
@@ -0,0 +1,197 @@
+//! Meeting auto-detection.
+//!
+//! Watches for the conferencing apps listed in `settings.meeting_apps`. When one of them enters
+//! a meeting we emit `meeting-detected` (and, for `auto_start` rules, start the backend if it
+//! is stopped); when the meeting ends we emit `meeting-ended`.
+//!
+//! On Windows a meeting means the app (or a child of it, as with WebView2-based clients) holds
+//! an active microphone capture session. Elsewhere we can only see that the app is running,
+//! and since Zoom and Teams usually stay resident all day, `auto_start` rules fall back to
+//! notifying there and the events carry `audio_activity: false`, so the UI can word its offer
+//! accordingly. Meetings held in a browser tab are not noticed on any platform.
+
+use std::collections::HashSet;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::{MeetingAction, SettingsStore};
+use crate::power;
+use crate::server_manager::{BackendState, ServerManager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+struct MeetingEvent<'a> {
+    process: &'a str,
+    action: MeetingAction,
+    /// Whether the app was seen using the microphone, rather than merely running.
+    audio_activity: bool,
+}
+
+/// Lower-case names of the processes that look like they are in a meeting.
+struct Candidates {
+    names: HashSet<String>,
+    /// Whether `names` comes from microphone activity rather than mere process presence.
+    audio_activity: bool,
+}
+
+pub fn watch(app: AppHandle) {
+    thread::spawn(move || {
+        #[cfg(windows)]
+        // SAFETY: initializes COM for this thread only; it lives as long as the thread.
+        let _ = unsafe {
+            windows::Win32::System::Com::CoInitializeEx(
+                None,
+                windows::Win32::System::Com::COINIT_MULTITHREADED,
+            )
+        };
+
+        let mut system = System::new();
+        let mut in_meeting: HashSet<String> = HashSet::new();
+        let mut warned_presence_only = false;
+
+        loop {
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::nothing(),
+            );
+            let candidates = candidates(&system);
+
+            let rules = app.state::<SettingsStore>().get().meeting_apps;
+            for rule in rules {
+                if rule.action == MeetingAction::Ignore {
+                    continue;
+                }
+                let name = rule.process.to_lowercase();
+                // Presence alone isn't a meeting; never start transcribing on it.
+                let action =
+                    if rule.action == MeetingAction::AutoStart && !candidates.audio_activity {
+                        if !warned_presence_only {
+                            tracing::warn!(
+                                "auto_start needs microphone activity detection, which is not \
+                                 available here; notifying instead"
+                            );
+                            warned_presence_only = true;
+                        }
+                        MeetingAction::Notify
+                    } else {
+                        rule.action
+                    };
+                let event = MeetingEvent {
+                    process: &rule.process,
+                    action,
+                    audio_activity: candidates.audio_activity,
+                };
+
+                match (candidates.names.contains(&name), in_meeting.contains(&name)) {
+                    (true, false) => {
+                        tracing::info!("meeting detected in {}", rule.process);
+                        in_meeting.insert(name);
+                        let _ = app.emit("meeting-detected", &event);
+                        if action == MeetingAction::AutoStart {
+                            auto_start(&app);
+                        }
+                    }
+                    (false, true) => {
+                        in_meeting.remove(&name);
+                        let _ = app.emit("meeting-ended", &event);
+                    }
+                    _ => {}
+                }
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+#[cfg(windows)]
+fn candidates(system: &System) -> Candidates {
+    use sysinfo::Pid;
+
+    /// How far up the process tree a capturing process is attributed to its app.
+    const MAX_ANCESTORS: usize = 4;
+
+    let pids = match capturing_pids() {
+        Ok(pids) => pids,
+        Err(e) => {
+            tracing::warn!("could not query audio sessions: {e}");
+            return running(system);
+        }
+    };
+
+    let mut names = HashSet::new();
+    for pid in pids {
+        let mut pid = Some(Pid::from_u32(pid));
+        for _ in 0..=MAX_ANCESTORS {
+            let Some(process) = pid.and_then(|pid| system.process(pid)) else {
+                break;
+            };
+            names.insert(process.name().to_string_lossy().to_lowercase());
+            pid = process.parent();
+        }
+    }
+    Candidates {
+        names,
+        audio_activity: true,
+    }
+}
+
+#[cfg(not(windows))]
+fn candidates(system: &System) -> Candidates {
+    running(system)
+}
+
+fn running(system: &System) -> Candidates {
+    Candidates {
+        names: system
+            .processes()
+            .values()
+            .map(|process| process.name().to_string_lossy().to_lowercase())
+            .collect(),
+        audio_activity: false,
+    }
+}
+
+/// Ids of the processes with an active capture session on any microphone.
+#[cfg(windows)]
+fn capturing_pids() -> windows::core::Result<HashSet<u32>> {
+    use windows::core::Interface;
+    use windows::Win32::Media::Audio::{
+        eCapture, AudioSessionStateActive, IAudioSessionControl2, IAudioSessionManager2,
+        IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+    let mut pids = HashSet::new();
+    // SAFETY: COM calls on interfaces we own; `watch` initialized COM on this thread.
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        let devices = enumerator.EnumAudioEndpoints(eCapture, DEVICE_STATE_ACTIVE)?;
+        for i in 0..devices.GetCount()? {
+            let manager: IAudioSessionManager2 = devices.Item(i)?.Activate(CLSCTX_ALL, None)?;
+            let sessions = manager.GetSessionEnumerator()?;
+            for j in 0..sessions.GetCount()? {
+                let session = sessions.GetSession(j)?;
+                if session.GetState()? == AudioSessionStateActive {
+                    pids.insert(session.cast::<IAudioSessionControl2>()?.GetProcessId()?);
+                }
+            }
+        }
+    }
+    Ok(pids)
+}
+
+fn auto_start(app: &AppHandle) {
+    let manager = app.state::<ServerManager>();
+    if manager.state() == BackendState::Stopped {
+        let args = power::backend_args(app, &app.state::<SettingsStore>().get());
+        manager.start(args);
+    }
+}
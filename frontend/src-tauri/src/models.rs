@@ -0,0 +1,312 @@
+//! Whisper model manager.
+//!
+//! Lists the ggml models published for whisper.cpp, downloads them into the app data dir with
+//! resumable progress events and verifies each file against the SHA256 published by the host.
+//! Only one download per model runs at a time, since they would share the partial file.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Emit a progress event at most once per this many downloaded bytes.
+const PROGRESS_STEP: u64 = 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// A download that receives nothing for this long is abandoned; it can be resumed later.
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Models we know how to fetch, with their approximate size on disk in MB.
+const CATALOG: &[(&str, u64)] = &[
+    ("tiny", 75),
+    ("tiny.en", 75),
+    ("base", 142),
+    ("base.en", 142),
+    ("small", 466),
+    ("small.en", 466),
+    ("medium", 1533),
+    ("medium.en", 1533),
+    ("large-v3-turbo", 1624),
+    ("large-v3", 3095),
+];
+
+#[derive(Clone, Serialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub file_name: String,
+    pub size_mb: u64,
+    pub installed: bool,
+    pub path: Option<PathBuf>,
+}
+
+/// Names of the models being downloaded right now.
+#[derive(Default)]
+pub struct Downloads {
+    active: Mutex<HashSet<String>>,
+}
+
+/// Marks a model as downloading until dropped.
+struct DownloadGuard {
+    app: AppHandle,
+    name: String,
+}
+
+impl DownloadGuard {
+    fn acquire(app: &AppHandle, name: &str) -> Result<Self, String> {
+        let downloads = app.state::<Downloads>();
+        if !downloads.active.lock().unwrap().insert(name.to_string()) {
+            return Err(format!("'{name}' is already being downloaded"));
+        }
+        Ok(Self {
+            app: app.clone(),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        let downloads = self.app.state::<Downloads>();
+        downloads.active.lock().unwrap().remove(&self.name);
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct DownloadProgress<'a> {
+    name: &'a str,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+pub fn file_name(name: &str) -> String {
+    format!("ggml-{name}.bin")
+}
+
+/// Whether `model` is an English-only (`.en`) model, quantized variants included. whisper
+/// transcribes English with those whatever language it is given.
+pub fn english_only(model: &Path) -> bool {
+    model
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(".en.") || name.contains(".en-"))
+}
+
+pub fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("models"))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_models(app: AppHandle) -> Result<Vec<ModelInfo>, String> {
+    let dir = models_dir(&app)?;
+    let mut models: Vec<ModelInfo> = CATALOG
+        .iter()
+        .map(|&(name, size_mb)| {
+            let file_name = file_name(name);
+            let path = dir.join(&file_name);
+            let installed = path.is_file();
+            ModelInfo {
+                name: name.to_string(),
+                file_name,
+                size_mb,
+                installed,
+                path: installed.then_some(path),
+            }
+        })
+        .collect();
+
+    // Also surface models the user dropped into the folder by hand.
+    if let Ok(mut entries) = fs::read_dir(&dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else {
+                continue;
+            };
+            if !file_name.ends_with(".bin") || models.iter().any(|m| m.file_name == file_name) {
+                continue;
+            }
+            let size_mb = entry
+                .metadata()
+                .await
+                .map(|m| m.len() / 1_000_000)
+                .unwrap_or(0);
+            models.push(ModelInfo {
+                name: file_name
+                    .trim_start_matches("ggml-")
+                    .trim_end_matches(".bin")
+                    .to_string(),
+                file_name: file_name.to_string(),
+                size_mb,
+                installed: true,
+                path: Some(path),
+            });
+        }
+    }
+
+    Ok(models)
+}
+
+/// Download `name` into the models dir, resuming a previous partial download if there is one.
+/// Progress is reported through `model-download-progress` events.
+#[tauri::command]
+pub async fn download_model(app: AppHandle, name: String) -> Result<ModelInfo, String> {
+    let &(_, size_mb) = CATALOG
+        .iter()
+        .find(|(known, _)| *known == name)
+        .ok_or_else(|| format!("unknown model '{name}'"))?;
+
+    let _guard = DownloadGuard::acquire(&app, &name)?;
+    let dir = models_dir(&app)?;
+    fs::create_dir_all(&dir).await.map_err(|e| e.to_string())?;
+
+    let file_name = file_name(&name);
+    let dest = dir.join(&file_name);
+    let partial = dir.join(format!("{file_name}.part"));
+    let url = format!("{MODEL_BASE_URL}/{file_name}");
+
+    let expected_sha256 = fetch_expected_sha256(&url).await?;
+
+    let mut downloaded = fs::metadata(&partial).await.map(|m| m.len()).unwrap_or(0);
+    let client = http_client().build().map_err(|e| e.to_string())?;
+    let mut request = client.get(&url);
+    if downloaded > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={downloaded}-"));
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+
+    // 416 means the partial file already holds the whole model; go straight to verification.
+    if response.status() != reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        let response = response.error_for_status().map_err(|e| e.to_string())?;
+        let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            downloaded = 0;
+        }
+        let total = response.content_length().map(|len| len + downloaded);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&partial)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut stream = response.bytes_stream();
+        let mut last_reported = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| e.to_string())?;
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            downloaded += chunk.len() as u64;
+
+            if downloaded - last_reported >= PROGRESS_STEP {
+                last_reported = downloaded;
+                let _ = app.emit(
+                    "model-download-progress",
+                    DownloadProgress {
+                        name: &name,
+                        downloaded,
+                        total,
+                    },
+                );
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+
+        let _ = app.emit(
+            "model-download-progress",
+            DownloadProgress {
+                name: &name,
+                downloaded,
+                total,
+            },
+        );
+    }
+
+    let actual_sha256 = sha256_file(&partial).await?;
+    if actual_sha256 != expected_sha256 {
+        let _ = fs::remove_file(&partial).await;
+        return Err(format!(
+            "checksum mismatch for {file_name}: expected {expected_sha256}, got {actual_sha256}"
+        ));
+    }
+    fs::rename(&partial, &dest)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ModelInfo {
+        name,
+        file_name,
+        size_mb,
+        installed: true,
+        path: Some(dest),
+    })
+}
+
+/// Hugging Face publishes the SHA256 of LFS files in the `X-Linked-Etag` header of the
+/// redirect it answers `resolve` URLs with, so ask for it without following the redirect.
+async fn fetch_expected_sha256(url: &str) -> Result<String, String> {
+    let client = http_client()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.head(url).send().await.map_err(|e| e.to_string())?;
+
+    response
+        .headers()
+        .get("x-linked-etag")
+        .and_then(|value| value.to_str().ok())
+        .map(|etag| etag.trim_matches('"').to_ascii_lowercase())
+        .ok_or_else(|| format!("no checksum published for {url}"))
+}
+
+/// Client settings for talking to the model host: a stalled connection fails the command
+/// instead of hanging it, however long the whole download takes.
+fn http_client() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+}
+
+async fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).await.map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buf).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_only_models_are_recognized_by_name() {
+        assert!(english_only(Path::new("/models/ggml-small.en.bin")));
+        assert!(english_only(Path::new("ggml-base.en-q5_1.bin")));
+        assert!(!english_only(Path::new("/models/ggml-small.bin")));
+        assert!(!english_only(Path::new("ggml-large-v3-turbo.bin")));
+        assert!(!english_only(Path::new("/models.en/ggml-tiny.bin")));
+    }
+}
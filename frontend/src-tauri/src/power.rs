@@ -0,0 +1,385 @@
+//! Power-aware throttling.
+//!
+//! Polls whether the machine runs on battery or in a power-saver mode. While it does (and
+//! `settings.power.throttle_on_battery` is on) the backend runs with fewer whisper threads and,
+//! if one is configured and downloaded, a smaller model. Every change is emitted as
+//! `power-mode-changed` so the UI can show the degraded mode, and the backend is restarted when
+//! its arguments change.
+//!
+//! Waking from sleep is reported by the OS on Windows. On Linux and macOS it shows as growth in
+//! the gap between a clock that keeps counting while suspended and one that doesn't, so clock
+//! changes and stalled threads don't count. The capture device is usually gone after a resume
+//! while ffmpeg keeps running, so a backend we supervise is restarted and `system-resumed` is
+//! emitted.
+
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config::{Settings, SettingsStore};
+use crate::models;
+use crate::server_manager::{BackendState, ServerManager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const RESUME_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Shorter suspends leave the capture device alone often enough not to restart for them.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const MIN_SLEEP: Duration = Duration::from_secs(2);
+/// `whisper_threads` default in brain_native.py, used when no thread count is configured.
+const BACKEND_DEFAULT_THREADS: u32 = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub power_saver: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct PowerModeChanged {
+    #[serde(flatten)]
+    state: PowerState,
+    throttled: bool,
+}
+
+/// Last observed power state, managed so argument derivation doesn't have to probe.
+pub struct Power {
+    state: Mutex<PowerState>,
+}
+
+impl Power {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(probe()),
+        }
+    }
+
+    pub fn get(&self) -> PowerState {
+        *self.state.lock().unwrap()
+    }
+}
+
+fn throttled(settings: &Settings, state: PowerState) -> bool {
+    settings.power.throttle_on_battery && (state.on_battery || state.power_saver)
+}
+
+/// Backend arguments for `settings` under the current power state. Use this rather than
+/// `Settings::backend_args` whenever starting the backend.
+pub fn backend_args(app: &AppHandle, settings: &Settings) -> Vec<String> {
+    if !throttled(settings, app.state::<Power>().get()) {
+        return settings.backend_args();
+    }
+
+    let battery_model = settings.power.battery_model.as_ref().and_then(|model| {
+        let path = models::models_dir(app).ok()?.join(models::file_name(model));
+        path.is_file().then_some(path)
+    });
+    throttle(settings, battery_model).backend_args()
+}
+
+/// `settings` as they apply while throttled: fewer threads and, if given, the battery model.
+fn throttle(settings: &Settings, battery_model: Option<PathBuf>) -> Settings {
+    let mut settings = settings.clone();
+    let threads = settings.compute.threads.unwrap_or(BACKEND_DEFAULT_THREADS);
+    settings.compute.threads = Some(
+        settings
+            .power
+            .battery_threads
+            .unwrap_or((threads / 2).max(1)),
+    );
+    if battery_model.is_some() {
+        settings.whisper_model = battery_model;
+    }
+    settings
+}
+
+pub fn watch(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let state = probe();
+        let power = app.state::<Power>();
+        if power.get() == state {
+            continue;
+        }
+
+        let settings = app.state::<SettingsStore>().get();
+        let previous_args = backend_args(&app, &settings);
+        *power.state.lock().unwrap() = state;
+        let args = backend_args(&app, &settings);
+
+        let throttled = throttled(&settings, state);
+        tracing::info!("power state changed: {state:?}, throttled: {throttled}");
+        let _ = app.emit("power-mode-changed", PowerModeChanged { state, throttled });
+
+        let manager = app.state::<ServerManager>();
+        if args != previous_args && manager.state() != BackendState::Stopped {
+            manager.restart(args);
+        }
+    });
+}
+
+pub fn watch_resume(app: AppHandle) {
+    let resumes = match resumes() {
+        Ok(resumes) => resumes,
+        Err(e) => {
+            tracing::warn!("not watching for resume from sleep: {e}");
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        for () in resumes {
+            tracing::info!("resumed from sleep");
+            let _ = app.emit("system-resumed", ());
+
+            let manager = app.state::<ServerManager>();
+            if matches!(
+                manager.state(),
+                BackendState::Stopped | BackendState::External
+            ) {
+                continue;
+            }
+            let settings = app.state::<SettingsStore>().get();
+            manager.restart(backend_args(&app, &settings));
+        }
+    });
+}
+
+/// A message for every resume from sleep, as Windows reports them.
+#[cfg(windows)]
+fn resumes() -> Result<mpsc::Receiver<()>, String> {
+    use std::ffi::c_void;
+
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC,
+    };
+
+    /// Runs on a system thread for every power event; `context` is our `Sender`.
+    unsafe extern "system" fn on_power_event(
+        context: *const c_void,
+        event: u32,
+        _setting: *const c_void,
+    ) -> u32 {
+        // Sent on every resume, whether or not the user is back yet.
+        if event == PBT_APMRESUMEAUTOMATIC {
+            let sender = &*context.cast::<mpsc::Sender<()>>();
+            let _ = sender.send(());
+        }
+        0
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    // The registration lasts as long as the app, so the callback's data does too.
+    let sender: &'static mut mpsc::Sender<()> = Box::leak(Box::new(sender));
+    let parameters = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+        Callback: Some(on_power_event),
+        Context: std::ptr::from_mut(sender).cast(),
+    }));
+    let mut registration = std::ptr::null_mut();
+    // SAFETY: `parameters` and the sender it points to are never freed.
+    let error = unsafe {
+        PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            std::ptr::from_mut(parameters).cast::<c_void>() as HANDLE,
+            &mut registration,
+        )
+    };
+    if error != 0 {
+        return Err(std::io::Error::from_raw_os_error(error as i32).to_string());
+    }
+    Ok(receiver)
+}
+
+/// A message for every resume from sleep, polled from the suspend-time gap between two clocks.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn resumes() -> Result<mpsc::Receiver<()>, String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut asleep = time_asleep();
+        loop {
+            thread::sleep(RESUME_POLL_INTERVAL);
+            let now = time_asleep();
+            let slept = now.saturating_sub(asleep);
+            asleep = now;
+            if slept >= MIN_SLEEP {
+                tracing::debug!("asleep for about {} s", slept.as_secs());
+                if sender.send(()).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn resumes() -> Result<mpsc::Receiver<()>, String> {
+    Err("not supported on this platform".to_string())
+}
+
+/// Total time spent suspended since boot: both clocks start at boot, and only the first keeps
+/// counting while the machine sleeps. Neither is affected by changes to the wall clock.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn time_asleep() -> Duration {
+    #[cfg(target_os = "linux")]
+    let (with_sleep, without_sleep) = (libc::CLOCK_BOOTTIME, libc::CLOCK_MONOTONIC);
+    #[cfg(target_os = "macos")]
+    let (with_sleep, without_sleep) = (libc::CLOCK_MONOTONIC, libc::CLOCK_UPTIME_RAW);
+
+    let read = |clock: libc::clockid_t| {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `time` is a valid timespec to write into.
+        unsafe { libc::clock_gettime(clock, &mut time) };
+        Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+    };
+    read(with_sleep).saturating_sub(read(without_sleep))
+}
+
+#[tauri::command]
+pub fn get_power_state(app: AppHandle) -> PowerState {
+    app.state::<Power>().get()
+}
+
+#[cfg(windows)]
+fn probe() -> PowerState {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // SAFETY: SYSTEM_POWER_STATUS is plain data and GetSystemPowerStatus only writes into it.
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return PowerState::default();
+    }
+    PowerState {
+        // 0 is offline, 1 online, 255 unknown.
+        on_battery: status.ACLineStatus == 0,
+        // Battery saver.
+        power_saver: status.SystemStatusFlag == 1,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn probe() -> PowerState {
+    use std::process::Command;
+
+    let pmset = |args: &[&str]| {
+        Command::new("pmset")
+            .args(args)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+            .unwrap_or_default()
+    };
+    PowerState {
+        on_battery: pmset(&["-g", "batt"]).contains("'Battery Power'"),
+        power_saver: pmset(&["-g"]).lines().any(|line| {
+            let mut fields = line.split_whitespace();
+            fields.next() == Some("lowpowermode") && fields.next() == Some("1")
+        }),
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn probe() -> PowerState {
+    use std::path::Path;
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let on_battery = std::fs::read_dir("/sys/class/power_supply")
+        .map(|entries| {
+            entries.filter_map(Result::ok).any(|entry| {
+                let supply = entry.path();
+                read(&supply.join("type")) == "Battery"
+                    && read(&supply.join("status")) == "Discharging"
+            })
+        })
+        .unwrap_or(false);
+    // power-profiles-daemon and friends switch the ACPI platform profile.
+    let power_saver = read(Path::new("/sys/firmware/acpi/platform_profile")) == "low-power";
+
+    PowerState {
+        on_battery,
+        power_saver,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threads_when_throttled(threads: Option<u32>, battery_threads: Option<u32>) -> Option<u32> {
+        let mut settings = Settings::default();
+        settings.compute.threads = threads;
+        settings.power.battery_threads = battery_threads;
+        throttle(&settings, None).compute.threads
+    }
+
+    #[test]
+    fn throttling_halves_threads() {
+        assert_eq!(
+            threads_when_throttled(None, None),
+            Some(BACKEND_DEFAULT_THREADS / 2)
+        );
+        assert_eq!(threads_when_throttled(Some(8), None), Some(4));
+        assert_eq!(threads_when_throttled(Some(3), None), Some(1));
+    }
+
+    #[test]
+    fn throttling_keeps_at_least_one_thread() {
+        assert_eq!(threads_when_throttled(Some(1), None), Some(1));
+    }
+
+    #[test]
+    fn battery_threads_override_halving() {
+        assert_eq!(threads_when_throttled(Some(8), Some(6)), Some(6));
+    }
+
+    #[test]
+    fn battery_model_replaces_the_model_only_when_given() {
+        let settings = Settings {
+            whisper_model: Some(PathBuf::from("large.bin")),
+            ..Settings::default()
+        };
+        assert_eq!(
+            throttle(&settings, None).whisper_model,
+            settings.whisper_model
+        );
+        assert_eq!(
+            throttle(&settings, Some(PathBuf::from("base.bin"))).whisper_model,
+            Some(PathBuf::from("base.bin"))
+        );
+    }
+
+    #[test]
+    fn only_battery_or_power_saver_throttles() {
+        let mut settings = Settings::default();
+        let battery = PowerState {
+            on_battery: true,
+            power_saver: false,
+        };
+        let saver = PowerState {
+            on_battery: false,
+            power_saver: true,
+        };
+        assert!(!throttled(&settings, PowerState::default()));
+        assert!(throttled(&settings, battery));
+        assert!(throttled(&settings, saver));
+
+        settings.power.throttle_on_battery = false;
+        assert!(!throttled(&settings, battery));
+    }
+}
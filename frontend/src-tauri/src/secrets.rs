@@ -0,0 +1,49 @@
+//! API keys and tokens in the OS keychain.
+//!
+//! Credentials are stored with the platform credential store (Windows Credential Manager, macOS
+//! Keychain, Secret Service on Linux) under the app identifier, never in `settings.json`. The
+//! frontend can set, clear and check for a secret but never read one back.
+
+use tauri::AppHandle;
+
+fn entry(app: &AppHandle, name: &str) -> Result<keyring::Entry, String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!("invalid secret name '{name}'"));
+    }
+
+    keyring::Entry::new(&app.config().identifier, name).map_err(|e| e.to_string())
+}
+
+/// Look a secret up for use inside the app; `None` if it was never set.
+pub fn get(app: &AppHandle, name: &str) -> Result<Option<String>, String> {
+    match entry(app, name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn set_secret(app: AppHandle, name: String, value: String) -> Result<(), String> {
+    entry(&app, &name)?
+        .set_password(&value)
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a secret; clearing one that was never set is not an error.
+#[tauri::command]
+pub fn clear_secret(app: AppHandle, name: String) -> Result<(), String> {
+    match entry(&app, &name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn has_secret(app: AppHandle, name: String) -> Result<bool, String> {
+    get(&app, &name).map(|value| value.is_some())
+}
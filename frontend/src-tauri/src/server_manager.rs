@@ -0,0 +1,709 @@
+//! Backend process supervisor.
+//!
+//! Spawns the Python engine (`backend/brain_native.py`, which in turn drives ffmpeg and
+//! whisper-stream-stdin) when the app starts, restarts it with exponential backoff when it dies
+//! and reports its health to the frontend. If a backend is already listening when the app starts
+//! (e.g. launched by `start_simple.ps1`) it is left alone while it keeps answering; once it
+//! stops, we spawn our own.
+//!
+//! The backend runs in its own process group (a job object on Windows) so stopping it also
+//! takes down the ffmpeg and whisper-stream-stdin processes it started, which would otherwise
+//! keep the microphone open. The job dies with the app on Windows; on Unix the group gets
+//! SIGTERM when the app is interrupted, terminated or panics on its main thread, and on Linux
+//! also when it is killed outright.
+
+use std::io::{BufRead, BufReader, Read};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::State;
+
+/// Where brain_native.py serves the frontend WebSocket.
+pub const BACKEND_ADDR: ([u8; 4], u16) = ([127, 0, 0, 1], 9082);
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A backend that stayed up this long is considered healthy again and resets the backoff.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const EXTERNAL_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Missed probes in a row after which an external backend counts as gone.
+const EXTERNAL_MISSES: u32 = 3;
+/// How long the backend gets to greet a client, and to confirm a pause or resume.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long the backend gets to shut its pipeline down after SIGTERM before it is killed.
+#[cfg(unix)]
+const STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendState {
+    Starting,
+    Running,
+    /// Someone else started the backend; we watch it and take over when it goes away.
+    External,
+    Restarting,
+    Stopped,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackendHealth {
+    pub state: BackendState,
+    pub pid: Option<u32>,
+    pub restarts: u32,
+    pub reachable: bool,
+    pub last_error: Option<String>,
+}
+
+struct Inner {
+    state: BackendState,
+    child: Option<BackendProcess>,
+    /// Extra `brain_native.py` arguments, derived from the user's settings.
+    args: Vec<String>,
+    restarts: u32,
+    last_error: Option<String>,
+    /// Bumped on every (re)start request so a superseded supervisor thread exits.
+    generation: u64,
+}
+
+#[derive(Clone)]
+pub struct ServerManager {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ServerManager {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                state: BackendState::Stopped,
+                child: None,
+                args: Vec::new(),
+                restarts: 0,
+                last_error: None,
+                generation: 0,
+            })),
+        }
+    }
+
+    /// Start supervising the backend with `args`. Does nothing unless the backend is stopped or
+    /// failed, so a backend that is starting, running or restarting is never spawned twice.
+    pub fn start(&self, args: Vec<String>) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.args = args;
+            if !matches!(inner.state, BackendState::Stopped | BackendState::Failed)
+                || inner.child.is_some()
+            {
+                return;
+            }
+        }
+
+        // Only a backend we don't own counts as external.
+        if backend_reachable() {
+            tracing::info!(
+                "backend already listening on {:?}, not spawning one",
+                BACKEND_ADDR
+            );
+            let generation = {
+                let mut inner = self.inner.lock().unwrap();
+                if !matches!(inner.state, BackendState::Stopped | BackendState::Failed) {
+                    return;
+                }
+                inner.generation += 1;
+                inner.state = BackendState::External;
+                inner.generation
+            };
+            let manager = self.clone();
+            thread::spawn(move || manager.watch_external(generation));
+            return;
+        }
+
+        let generation = {
+            let mut inner = self.inner.lock().unwrap();
+            if !matches!(inner.state, BackendState::Stopped | BackendState::Failed) {
+                return;
+            }
+            inner.generation += 1;
+            inner.state = BackendState::Starting;
+            inner.last_error = None;
+            inner.generation
+        };
+
+        let manager = self.clone();
+        thread::spawn(move || manager.supervise(generation));
+    }
+
+    /// Kill the backend we spawned and stop restarting it.
+    pub fn stop(&self) {
+        let child = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.generation += 1;
+            inner.state = BackendState::Stopped;
+            inner.child.take()
+        };
+        if let Some(child) = child {
+            child.terminate();
+        }
+    }
+
+    /// Restart the backend we spawned with new arguments. An external backend keeps running
+    /// as is; the arguments only apply once we spawn one ourselves.
+    pub fn restart(&self, args: Vec<String>) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.state == BackendState::External {
+                inner.args = args;
+                return;
+            }
+        }
+        self.stop();
+        self.start(args);
+    }
+
+    /// The supervisor state alone, without probing the port like `health` does.
+    pub fn state(&self) -> BackendState {
+        self.inner.lock().unwrap().state
+    }
+
+    pub fn health(&self) -> BackendHealth {
+        let mut health = snapshot(&self.inner.lock().unwrap());
+        // Probed after the lock is released; the connect can take a while.
+        health.reachable = backend_reachable();
+        health
+    }
+
+    /// Like `health`, but gives up instead of blocking when the state is locked, which matters
+    /// when called from a panic hook.
+    pub fn try_health(&self) -> Option<BackendHealth> {
+        let mut health = self.inner.try_lock().ok().map(|inner| snapshot(&inner))?;
+        health.reachable = backend_reachable();
+        Some(health)
+    }
+
+    /// Poll a backend we didn't start and spawn our own once it stops answering.
+    fn watch_external(&self, generation: u64) {
+        let mut misses = 0;
+        loop {
+            thread::sleep(EXTERNAL_POLL_INTERVAL);
+            if self.inner.lock().unwrap().generation != generation {
+                return;
+            }
+            misses = if backend_reachable() { 0 } else { misses + 1 };
+            if misses < EXTERNAL_MISSES {
+                continue;
+            }
+
+            let args = {
+                let mut inner = self.inner.lock().unwrap();
+                if inner.generation != generation {
+                    return;
+                }
+                tracing::warn!("external backend stopped answering, starting our own");
+                inner.state = BackendState::Failed;
+                inner.last_error = Some("external backend stopped answering".to_string());
+                inner.args.clone()
+            };
+            self.start(args);
+            return;
+        }
+    }
+
+    fn supervise(&self, generation: u64) {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let started = Instant::now();
+            let args = self.inner.lock().unwrap().args.clone();
+            match spawn_backend(&args) {
+                Ok(child) => {
+                    let mut inner = self.inner.lock().unwrap();
+                    // Superseded, or somehow another backend is already ours: never replace it.
+                    if inner.generation != generation || inner.child.is_some() {
+                        drop(inner);
+                        child.terminate();
+                        return;
+                    }
+                    tracing::info!("backend started (pid {})", child.id());
+                    #[cfg(unix)]
+                    BACKEND_GROUP.store(child.id() as i32, Ordering::SeqCst);
+                    inner.child = Some(child);
+                    inner.state = BackendState::Running;
+                }
+                Err(e) => {
+                    tracing::error!("failed to start backend: {e}");
+                    let mut inner = self.inner.lock().unwrap();
+                    if inner.generation != generation {
+                        return;
+                    }
+                    inner.state = BackendState::Failed;
+                    inner.last_error = Some(e);
+                }
+            }
+
+            // Wait for the child to exit (or for someone to stop/replace us).
+            loop {
+                let mut inner = self.inner.lock().unwrap();
+                if inner.generation != generation {
+                    return;
+                }
+                let Some(child) = inner.child.as_mut() else {
+                    break;
+                };
+                let exited = match child.try_wait() {
+                    Ok(Some(status)) => {
+                        tracing::warn!("backend exited with {status}");
+                        inner.last_error = Some(format!("backend exited with {status}"));
+                        true
+                    }
+                    Ok(None) => false,
+                    Err(e) => {
+                        inner.last_error = Some(e.to_string());
+                        true
+                    }
+                };
+                if exited {
+                    // Take down whatever the dead backend left running.
+                    let child = inner.child.take();
+                    drop(inner);
+                    if let Some(child) = child {
+                        child.terminate();
+                    }
+                    break;
+                }
+                drop(inner);
+                thread::sleep(POLL_INTERVAL);
+            }
+
+            if started.elapsed() >= STABLE_UPTIME {
+                backoff = INITIAL_BACKOFF;
+            }
+            {
+                let mut inner = self.inner.lock().unwrap();
+                if inner.generation != generation {
+                    return;
+                }
+                inner.restarts += 1;
+                inner.state = BackendState::Restarting;
+            }
+            tracing::info!("restarting backend in {}s", backoff.as_secs());
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+/// Everything in `BackendHealth` except `reachable`, which callers fill in without the lock.
+fn snapshot(inner: &Inner) -> BackendHealth {
+    BackendHealth {
+        state: inner.state,
+        pid: inner.child.as_ref().map(BackendProcess::id),
+        restarts: inner.restarts,
+        reachable: false,
+        last_error: inner.last_error.clone(),
+    }
+}
+
+/// Process group of the backend we own, for `kill_backend_group`; 0 while there is none.
+#[cfg(unix)]
+static BACKEND_GROUP: AtomicI32 = AtomicI32::new(0);
+
+/// Send SIGTERM to the backend's process group without touching the supervisor state. For
+/// exit paths that never reach `stop`; safe to call from a signal handler.
+#[cfg(unix)]
+pub fn kill_backend_group() {
+    let group = BACKEND_GROUP.load(Ordering::SeqCst);
+    if group > 0 {
+        // SAFETY: kill(2) is async-signal-safe and has no memory-safety preconditions.
+        unsafe { libc::kill(-group, libc::SIGTERM) };
+    }
+}
+
+/// Take the backend down when the app gets SIGINT, SIGTERM or SIGHUP (e.g. Ctrl+C under
+/// `tauri dev`), then die of the signal as usual. `RunEvent::Exit` never fires in that case.
+#[cfg(unix)]
+pub fn install_exit_guard() {
+    extern "C" fn on_signal(signal: libc::c_int) {
+        kill_backend_group();
+        // SAFETY: signal(2) and raise(3) are async-signal-safe.
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+    }
+
+    for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        // SAFETY: `on_signal` only makes async-signal-safe calls.
+        unsafe {
+            libc::signal(
+                signal,
+                on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            )
+        };
+    }
+}
+
+fn backend_reachable() -> bool {
+    TcpStream::connect_timeout(&SocketAddr::from(BACKEND_ADDR), Duration::from_millis(300)).is_ok()
+}
+
+pub type BackendSocket = tungstenite::WebSocket<TcpStream>;
+
+/// Open a WebSocket client connection to the backend; reads give up after `read_timeout`.
+pub fn connect_websocket(read_timeout: Duration) -> Result<BackendSocket, String> {
+    let stream =
+        TcpStream::connect_timeout(&SocketAddr::from(BACKEND_ADDR), Duration::from_secs(2))
+            .map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(read_timeout))
+        .map_err(|e| e.to_string())?;
+    let url = format!("ws://127.0.0.1:{}", BACKEND_ADDR.1);
+    tungstenite::client(url, stream)
+        .map(|(socket, _)| socket)
+        .map_err(|e| e.to_string())
+}
+
+/// Send a backend message such as `{"type":"ping"}`.
+pub fn send_json(socket: &mut BackendSocket, message: serde_json::Value) -> Result<(), String> {
+    socket
+        .send(tungstenite::Message::text(message.to_string()))
+        .map_err(|e| e.to_string())
+}
+
+/// Read the next message from the backend. `Ok(None)` means the read timed out or the frame
+/// wasn't JSON text; real connection errors are `Err`.
+pub fn read_json(socket: &mut BackendSocket) -> Result<Option<serde_json::Value>, String> {
+    match socket.read() {
+        Ok(message) => Ok(message
+            .to_text()
+            .ok()
+            .and_then(|text| serde_json::from_str(text).ok())),
+        Err(tungstenite::Error::Io(e))
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Pause or resume transcription in the running backend. Waits until the backend's status
+/// confirms it, so an `Ok` means it really took effect.
+pub fn set_paused(paused: bool) -> Result<(), String> {
+    change_paused(|_| paused).map(|_| ())
+}
+
+/// Pause a transcribing backend or resume a paused one; returns whether it is paused now.
+pub fn toggle_paused() -> Result<bool, String> {
+    change_paused(|paused| !paused)
+}
+
+/// `want` gets whether the backend is paused now and returns whether it should be.
+fn change_paused(want: impl FnOnce(bool) -> bool) -> Result<bool, String> {
+    let mut socket = connect_websocket(Duration::from_millis(250))
+        .map_err(|e| format!("backend not reachable: {e}"))?;
+    let result = request_paused(&mut socket, want);
+    let _ = socket.close(None);
+    result
+}
+
+fn request_paused(
+    socket: &mut BackendSocket,
+    want: impl FnOnce(bool) -> bool,
+) -> Result<bool, String> {
+    // Every client is greeted with the current status.
+    let paused = read_status(socket)?;
+    let target = want(paused);
+    if target == paused {
+        return Ok(paused);
+    }
+    let kind = if target { "pause" } else { "resume" };
+    send_json(socket, serde_json::json!({ "type": kind }))?;
+    // The change is broadcast to every client, us included.
+    while read_status(socket)? != target {}
+    Ok(target)
+}
+
+/// The `paused` flag of the next status message from the backend.
+fn read_status(socket: &mut BackendSocket) -> Result<bool, String> {
+    let started = Instant::now();
+    while started.elapsed() < STATUS_TIMEOUT {
+        if let Some(message) = read_json(socket)? {
+            if message["type"] == "status" {
+                return Ok(message["paused"].as_bool().unwrap_or(false));
+            }
+        }
+    }
+    Err("backend did not report its status".to_string())
+}
+
+/// The Python interpreter to run the backend with; override with `COPILOT_PYTHON`.
+pub fn python_executable() -> String {
+    std::env::var("COPILOT_PYTHON").unwrap_or_else(|_| "python".to_string())
+}
+
+/// Locate the repository root (the directory holding `backend/brain_native.py`), either from
+/// `COPILOT_ROOT` or by walking up from the current directory.
+pub fn project_root() -> Option<PathBuf> {
+    if let Ok(root) = std::env::var("COPILOT_ROOT") {
+        return Some(PathBuf::from(root));
+    }
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| dir.join("backend").join("brain_native.py").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// A spawned backend together with everything it starts.
+struct BackendProcess {
+    child: Child,
+    /// Job object holding the process tree; closing it kills whatever is still inside.
+    #[cfg(windows)]
+    job: job::Job,
+}
+
+impl BackendProcess {
+    fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// Stop the backend and every process it started. On Unix the group gets SIGTERM first
+    /// so brain_native.py can stop its pipeline, then SIGKILL after `STOP_TIMEOUT`.
+    #[cfg(unix)]
+    fn terminate(mut self) {
+        // The group id is the backend's pid (see `process_group(0)` in `spawn_backend`).
+        let pid = self.child.id() as libc::pid_t;
+        let _ = BACKEND_GROUP.compare_exchange(pid, 0, Ordering::SeqCst, Ordering::SeqCst);
+        let group = -pid;
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe { libc::kill(group, libc::SIGTERM) };
+
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while matches!(self.child.try_wait(), Ok(None)) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+        // Also reaps stragglers (ffmpeg, whisper) if the backend itself already exited.
+        unsafe { libc::kill(group, libc::SIGKILL) };
+        let _ = self.child.wait();
+    }
+
+    /// Stop the backend and every process it started. Windows has no SIGTERM for a process
+    /// without a console, so the whole job is terminated.
+    #[cfg(windows)]
+    fn terminate(mut self) {
+        self.job.terminate();
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn terminate(mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    pub struct Job(HANDLE);
+
+    // SAFETY: a job object handle may be used and closed from any thread.
+    unsafe impl Send for Job {}
+
+    impl Job {
+        /// Put `child` (and everything it spawns from now on) into a new job that is killed
+        /// when the job handle closes, even if we crash.
+        pub fn assign(child: &Child) -> Result<Self, String> {
+            // SAFETY: plain Win32 calls on handles we own; `info` outlives the call using it.
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+                let job = Job(handle);
+
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let configured = SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    std::ptr::addr_of!(info).cast(),
+                    std::mem::size_of_val(&info) as u32,
+                );
+                if configured == 0
+                    || AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) == 0
+                {
+                    return Err(std::io::Error::last_os_error().to_string());
+                }
+                Ok(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the handle is valid until `drop`.
+            unsafe { TerminateJobObject(self.0, 1) };
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the handle is valid and closed exactly once.
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+}
+
+fn spawn_backend(args: &[String]) -> Result<BackendProcess, String> {
+    let root = project_root().ok_or("could not find backend/brain_native.py")?;
+
+    let mut command = Command::new(python_executable());
+    command
+        .arg(Path::new("backend").join("brain_native.py"))
+        .args(args)
+        .current_dir(&root)
+        // Python block-buffers piped stdout; we want its lines as they happen.
+        .env("PYTHONUNBUFFERED", "1")
+        // Piped stdio otherwise uses the ANSI code page on Windows, which can't encode the
+        // emoji brain_native.py logs.
+        .env("PYTHONIOENCODING", "utf-8")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Don't flash a console window for the backend on Windows.
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    // Own process group, so `terminate` can signal ffmpeg and whisper along with Python.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+
+        // A group of its own also means nothing reaches the backend when the app dies without
+        // stopping it. On Linux, have the kernel send it SIGTERM (which brain_native.py
+        // handles by stopping its pipeline) then. The signal follows the spawning thread, the
+        // supervisor, which lives as long as the child is ours.
+        #[cfg(target_os = "linux")]
+        {
+            let parent = std::process::id() as libc::pid_t;
+            // SAFETY: the closure only makes async-signal-safe calls.
+            unsafe {
+                command.pre_exec(move || {
+                    if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                    // The app died before prctl took effect.
+                    if libc::getppid() != parent {
+                        return Err(std::io::Error::from_raw_os_error(libc::ESRCH));
+                    }
+                    Ok(())
+                });
+            }
+        }
+    }
+
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    if let Some(stdout) = child.stdout.take() {
+        forward_output(stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_output(stderr);
+    }
+
+    #[cfg(windows)]
+    let job = match job::Job::assign(&child) {
+        Ok(job) => job,
+        Err(e) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("could not create job object for the backend: {e}"));
+        }
+    };
+
+    Ok(BackendProcess {
+        child,
+        #[cfg(windows)]
+        job,
+    })
+}
+
+/// Re-log the backend's output under the `backend` target so it lands in our log files. Keeps
+/// draining the pipe through invalid UTF-8; a full pipe would block the backend.
+fn forward_output(output: impl Read + Send + 'static) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(output);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&line);
+                    tracing::info!(target: "backend", "{}", text.trim_end());
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn backend_health(manager: State<'_, ServerManager>) -> BackendHealth {
+    manager.health()
+}
+
+/// Stop sending transcripts; the audio pipeline keeps running so resuming is instant.
+#[tauri::command]
+pub async fn pause_transcription() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(|| set_paused(true))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn resume_transcription() -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(|| set_paused(false))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Stop and start the backend with its current arguments. Async so the wait for the old
+/// backend to exit happens on the blocking pool rather than the main thread.
+#[tauri::command]
+pub async fn restart_backend(manager: State<'_, ServerManager>) -> Result<BackendHealth, String> {
+    let manager = manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let args = manager.inner.lock().unwrap().args.clone();
+        manager.stop();
+        manager.start(args);
+        manager.health()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,493 @@
+//! First-run setup wizard backend.
+//!
+//! The wizard probes the machine, recommends a whisper model from the probe, downloads it,
+//! writes the initial settings and finishes with a short mic test against the backend, so a new
+//! user reaches a first transcript without reading docs.
+//!
+//! The same probe drives automatic model selection: with `settings.auto_model` on, the largest
+//! downloaded model the machine can run in real time is picked at startup and whenever the UI
+//! asks for a re-evaluation.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+use tauri::{AppHandle, Manager};
+
+use crate::config::{self, ComputeOptions, Settings, SettingsStore};
+use crate::server_manager::{self, BackendSocket, BackendState, ServerManager};
+use crate::{models, power};
+
+#[derive(Clone, Serialize)]
+pub struct HardwareInfo {
+    pub cpu: String,
+    pub physical_cores: usize,
+    pub logical_cores: usize,
+    pub avx2: bool,
+    pub avx512: bool,
+    pub neon: bool,
+    pub memory_mb: u64,
+    pub gpus: Vec<GpuInfo>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct GpuInfo {
+    pub name: String,
+    /// Dedicated memory; `None` for unified memory or when the driver doesn't say.
+    pub vram_mb: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct Recommendation {
+    pub hardware: HardwareInfo,
+    pub model: String,
+}
+
+#[derive(Serialize)]
+pub struct ModelSelection {
+    #[serde(flatten)]
+    pub recommendation: Recommendation,
+    /// Whether the recommended model is downloaded.
+    pub installed: bool,
+    /// The model automatic selection settles on: the recommended one if it is downloaded,
+    /// otherwise the largest downloaded one the machine still runs in real time.
+    pub chosen: Option<String>,
+    /// Whether `whisper_model` now points at `chosen`.
+    pub selected: bool,
+}
+
+/// How long the mic test listens.
+const MIC_TEST_DURATION: Duration = Duration::from_secs(5);
+/// How long to wait for the backend to come up (and load its model) before the mic test.
+const BACKEND_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to wait for the backend's greeting before assuming it isn't paused.
+const GREETING_TIMEOUT: Duration = Duration::from_secs(2);
+const READ_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Memory whisper.cpp needs to run each model size, in MB (from the whisper.cpp README).
+const MODEL_MEMORY_MB: &[(&str, u64)] = &[
+    ("large-v3", 3900),
+    ("large-v3-turbo", 2100),
+    ("medium", 2100),
+    ("small", 852),
+    ("base", 388),
+    ("tiny", 273),
+];
+
+pub fn probe() -> HardwareInfo {
+    let system = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    let logical_cores = system.cpus().len();
+
+    HardwareInfo {
+        cpu: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .unwrap_or_default(),
+        physical_cores: system.physical_core_count().unwrap_or(logical_cores),
+        logical_cores,
+        avx2: cpu_feature("avx2"),
+        avx512: cpu_feature("avx512f"),
+        neon: cpu_feature("neon"),
+        memory_mb: system.total_memory() / (1024 * 1024),
+        gpus: probe_gpus(),
+    }
+}
+
+fn cpu_feature(feature: &str) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        match feature {
+            "avx2" => std::arch::is_x86_feature_detected!("avx2"),
+            "avx512f" => std::arch::is_x86_feature_detected!("avx512f"),
+            _ => false,
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        feature == "neon" && std::arch::is_aarch64_feature_detected!("neon")
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = feature;
+        false
+    }
+}
+
+/// NVIDIA GPUs via `nvidia-smi`; Apple silicon reports its integrated GPU (Metal).
+fn probe_gpus() -> Vec<GpuInfo> {
+    let mut gpus = Vec::new();
+
+    if let Ok(output) = Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,memory.total",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+    {
+        if output.status.success() {
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let mut fields = line.split(',').map(str::trim);
+                if let Some(name) = fields.next().filter(|name| !name.is_empty()) {
+                    gpus.push(GpuInfo {
+                        name: name.to_string(),
+                        vram_mb: fields.next().and_then(|mb| mb.parse().ok()),
+                    });
+                }
+            }
+        }
+    }
+
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        gpus.push(GpuInfo {
+            name: "Apple silicon GPU (Metal)".to_string(),
+            vram_mb: None,
+        });
+    }
+
+    gpus
+}
+
+/// Pick the largest model the machine runs comfortably in real time with the configured compute
+/// options: only the GPU inference may use (none with `use_gpu` off, just `gpu_device` when
+/// that is set) counts. English-only variants are preferred when transcribing English, since
+/// they are more accurate at the same size.
+pub fn recommend(hardware: &HardwareInfo, compute: &ComputeOptions, language: &str) -> String {
+    model_for_budget(budget_mb(hardware, compute), language)
+}
+
+/// Memory, in MB, the models whisper runs in real time may use with `compute`.
+fn budget_mb(hardware: &HardwareInfo, compute: &ComputeOptions) -> u64 {
+    let gpus: Vec<&GpuInfo> = match compute.gpu_device {
+        _ if !compute.use_gpu => Vec::new(),
+        Some(device) => hardware.gpus.get(device as usize).into_iter().collect(),
+        None => hardware.gpus.iter().collect(),
+    };
+
+    match gpus.iter().filter_map(|gpu| gpu.vram_mb).max() {
+        // Leave headroom for the driver and whatever else is on the card.
+        Some(vram_mb) => vram_mb * 8 / 10,
+        // Unified memory: Metal can use a good share of RAM.
+        None if !gpus.is_empty() => hardware.memory_mb / 2,
+        // CPU only: memory is rarely the limit, speed is.
+        None => {
+            let fast = hardware.avx2 || hardware.neon;
+            match hardware.physical_cores {
+                cores if cores >= 8 && fast => 852,
+                cores if cores >= 4 && fast => 388,
+                _ => 273,
+            }
+        }
+    }
+}
+
+/// The largest model that fits in `budget_mb`, as a catalog name.
+fn model_for_budget(budget_mb: u64, language: &str) -> String {
+    models_for_budget(budget_mb, language).swap_remove(0)
+}
+
+/// Every model that fits in `budget_mb`, best first (never empty: tiny is the fallback).
+/// English-only models only qualify for English, where they come before the multilingual model
+/// of the same size.
+fn models_for_budget(budget_mb: u64, language: &str) -> Vec<String> {
+    let mut sizes: Vec<&str> = MODEL_MEMORY_MB
+        .iter()
+        .filter(|(_, needed)| *needed <= budget_mb)
+        .map(|(name, _)| *name)
+        .collect();
+    if sizes.is_empty() {
+        sizes.push("tiny");
+    }
+
+    let mut names = Vec::new();
+    for size in sizes {
+        if language == "en" && !size.starts_with("large") {
+            names.push(format!("{size}.en"));
+        }
+        names.push(size.to_string());
+    }
+    names
+}
+
+/// Point `settings.whisper_model` at the largest downloaded model the machine runs in real
+/// time, if automatic selection is on and the current model isn't a user-supplied file. (A
+/// model picked by hand through `set_settings` turns automatic selection off.)
+pub fn select_model(app: &AppHandle, settings: &mut Settings) -> Result<ModelSelection, String> {
+    let hardware = probe();
+    let dir = models::models_dir(app)?;
+    let candidates = models_for_budget(budget_mb(&hardware, &settings.compute), &settings.language);
+    let downloaded = |name: &String| dir.join(models::file_name(name)).is_file();
+
+    let model = candidates[0].clone();
+    let installed = downloaded(&model);
+    let chosen = candidates.into_iter().find(downloaded);
+    let chosen_path = chosen
+        .as_ref()
+        .map(|name| dir.join(models::file_name(name)));
+
+    let managed = |current: &PathBuf| current.starts_with(&dir);
+    if settings.auto_model
+        && chosen_path.is_some()
+        && settings.whisper_model.as_ref().map_or(true, managed)
+    {
+        settings.whisper_model = chosen_path.clone();
+    }
+
+    Ok(ModelSelection {
+        selected: chosen_path.is_some() && settings.whisper_model == chosen_path,
+        chosen,
+        installed,
+        recommendation: Recommendation { hardware, model },
+    })
+}
+
+/// Run the automatic selection against the stored settings before the backend first starts.
+pub fn select_model_on_start(app: &AppHandle, store: &SettingsStore) {
+    let mut settings = store.get();
+    if !settings.auto_model {
+        return;
+    }
+    let previous = settings.whisper_model.clone();
+    match select_model(app, &mut settings) {
+        Ok(selection) if settings.whisper_model != previous => {
+            tracing::info!(
+                "selected whisper model {}",
+                selection.chosen.as_deref().unwrap_or_default()
+            );
+            if let Err(e) = store.set(settings) {
+                tracing::warn!("could not save the selected model: {e}");
+            }
+        }
+        Ok(selection) if selection.chosen.is_none() => tracing::info!(
+            "no suitable whisper model downloaded; {} is recommended",
+            selection.recommendation.model
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("automatic model selection failed: {e}"),
+    }
+}
+
+#[tauri::command]
+pub fn probe_hardware() -> HardwareInfo {
+    probe()
+}
+
+#[tauri::command]
+pub fn recommend_model(app: AppHandle) -> Recommendation {
+    let hardware = probe();
+    let settings = app.state::<SettingsStore>().get();
+    let model = recommend(&hardware, &settings.compute, &settings.language);
+    Recommendation { hardware, model }
+}
+
+/// Probe the hardware again (after a GPU or driver change, say) and switch models if the
+/// choice changed. Async because a switch restarts the backend, which blocks until the old
+/// one has exited.
+#[tauri::command]
+pub async fn reevaluate_model(app: AppHandle) -> Result<ModelSelection, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        let selection = select_model(&app, &mut settings)?;
+        config::update(&app, &store, &app.state::<ServerManager>(), settings)?;
+        Ok(selection)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Download `model` (progress arrives as `model-download-progress` events), make it the
+/// configured model and mark first-run setup as done. Picking anything but the recommended
+/// model turns automatic selection off, as choosing one in the settings does.
+#[tauri::command]
+pub async fn complete_setup(app: AppHandle, model: String) -> Result<config::Settings, String> {
+    let downloaded = models::download_model(app.clone(), model.clone()).await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app.state::<SettingsStore>();
+        let mut settings = store.get();
+        if model != recommend(&probe(), &settings.compute, &settings.language) {
+            settings.auto_model = false;
+        }
+        settings.whisper_model = downloaded.path;
+        settings.setup_complete = true;
+        config::update(&app, &store, &app.state::<ServerManager>(), settings)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Listen for five seconds and return what the backend transcribed (empty if nothing was
+/// heard). Starts the backend if it is stopped or has failed.
+#[tauri::command]
+pub async fn mic_test(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app.state::<ServerManager>();
+        if matches!(
+            manager.state(),
+            BackendState::Stopped | BackendState::Failed
+        ) {
+            manager.start(power::backend_args(
+                &app,
+                &app.state::<SettingsStore>().get(),
+            ));
+        }
+        listen()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn listen() -> Result<String, String> {
+    let started = Instant::now();
+    let mut socket = loop {
+        match server_manager::connect_websocket(READ_TIMEOUT) {
+            Ok(socket) => break socket,
+            Err(e) if started.elapsed() > BACKEND_STARTUP_TIMEOUT => {
+                return Err(format!("backend did not start: {e}"))
+            }
+            Err(_) => std::thread::sleep(Duration::from_millis(500)),
+        }
+    };
+
+    // The backend greets every client with its status; remember whether the user had paused.
+    let greeted = Instant::now();
+    let paused = loop {
+        match server_manager::read_json(&mut socket)? {
+            Some(status) if status["type"] == "status" => {
+                break status["paused"].as_bool().unwrap_or(false)
+            }
+            _ if greeted.elapsed() > GREETING_TIMEOUT => break false,
+            _ => {}
+        }
+    };
+
+    // Transcripts aren't broadcast while paused.
+    if paused {
+        server_manager::send_json(&mut socket, json!({ "type": "resume" }))?;
+    }
+    let transcript = collect_transcript(&mut socket);
+    if paused {
+        if let Err(e) = server_manager::send_json(&mut socket, json!({ "type": "pause" })) {
+            tracing::warn!("could not pause the backend again after the mic test: {e}");
+        }
+    }
+    let _ = socket.close(None);
+
+    transcript
+}
+
+fn collect_transcript(socket: &mut BackendSocket) -> Result<String, String> {
+    let listening = Instant::now();
+    let mut transcript = Vec::new();
+    while listening.elapsed() < MIC_TEST_DURATION {
+        let Some(message) = server_manager::read_json(socket)? else {
+            continue;
+        };
+        if message["type"] == "transcript" {
+            if let Some(text) = message["text"].as_str() {
+                transcript.push(text.trim().to_string());
+            }
+        }
+    }
+    Ok(transcript.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hardware(physical_cores: usize, avx2: bool, gpus: &[Option<u64>]) -> HardwareInfo {
+        HardwareInfo {
+            cpu: String::new(),
+            physical_cores,
+            logical_cores: physical_cores * 2,
+            avx2,
+            avx512: false,
+            neon: false,
+            memory_mb: 16384,
+            gpus: gpus
+                .iter()
+                .map(|&vram_mb| GpuInfo {
+                    name: "gpu".to_string(),
+                    vram_mb,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn budget_picks_the_largest_model_that_fits() {
+        assert_eq!(model_for_budget(4000, "de"), "large-v3");
+        assert_eq!(model_for_budget(2100, "de"), "large-v3-turbo");
+        assert_eq!(model_for_budget(900, "de"), "small");
+        assert_eq!(model_for_budget(388, "de"), "base");
+        // Below every model's requirement we still fall back to tiny.
+        assert_eq!(model_for_budget(100, "de"), "tiny");
+    }
+
+    #[test]
+    fn english_prefers_en_variants_except_large() {
+        assert_eq!(model_for_budget(900, "en"), "small.en");
+        assert_eq!(model_for_budget(100, "en"), "tiny.en");
+        assert_eq!(model_for_budget(4000, "en"), "large-v3");
+        assert_eq!(model_for_budget(2100, "en"), "large-v3-turbo");
+        assert_eq!(model_for_budget(900, "auto"), "small");
+    }
+
+    #[test]
+    fn candidates_run_from_largest_to_smallest() {
+        assert_eq!(
+            models_for_budget(900, "en"),
+            ["small.en", "small", "base.en", "base", "tiny.en", "tiny"]
+        );
+        assert_eq!(
+            models_for_budget(2100, "de"),
+            ["large-v3-turbo", "medium", "small", "base", "tiny"]
+        );
+        assert_eq!(models_for_budget(100, "de"), ["tiny"]);
+        assert_eq!(models_for_budget(100, "en"), ["tiny.en", "tiny"]);
+    }
+
+    #[test]
+    fn cpu_budget_depends_on_cores_and_simd() {
+        let cpu = ComputeOptions::default();
+        assert_eq!(recommend(&hardware(8, true, &[]), &cpu, "de"), "small");
+        assert_eq!(recommend(&hardware(4, true, &[]), &cpu, "de"), "base");
+        assert_eq!(recommend(&hardware(16, false, &[]), &cpu, "de"), "tiny");
+    }
+
+    #[test]
+    fn gpu_budget_follows_compute_options() {
+        let machine = hardware(4, true, &[Some(24576), Some(4096)]);
+        let mut compute = ComputeOptions::default();
+        assert_eq!(recommend(&machine, &compute, "de"), "large-v3");
+
+        compute.gpu_device = Some(1);
+        assert_eq!(recommend(&machine, &compute, "de"), "large-v3-turbo");
+
+        // A device index that doesn't exist leaves only the CPU.
+        compute.gpu_device = Some(5);
+        assert_eq!(recommend(&machine, &compute, "de"), "base");
+
+        compute.gpu_device = None;
+        compute.use_gpu = false;
+        assert_eq!(recommend(&machine, &compute, "de"), "base");
+    }
+
+    #[test]
+    fn unified_memory_uses_half_the_ram() {
+        let machine = hardware(8, false, &[None]);
+        assert_eq!(
+            recommend(&machine, &ComputeOptions::default(), "de"),
+            "large-v3"
+        );
+    }
+}
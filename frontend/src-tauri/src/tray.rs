@@ -0,0 +1,160 @@
+//! System tray icon.
+//!
+//! Gives quick access to the backend (start/stop), pause/resume and the control window so the
+//! app can stay minimized all day. The icon gets a coloured badge while the backend is running
+//! (red) or failing (amber).
+
+use std::thread;
+use std::time::Duration;
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{TrayIcon, TrayIconBuilder};
+use tauri::{AppHandle, Manager};
+
+use crate::config::SettingsStore;
+use crate::power;
+use crate::server_manager::{self, BackendState, ServerManager};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const RECORDING_BADGE: [u8; 3] = [0xe5, 0x39, 0x35];
+const ERROR_BADGE: [u8; 3] = [0xfb, 0x8c, 0x00];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TrayState {
+    Idle,
+    Recording,
+    Error,
+}
+
+impl TrayState {
+    fn from_backend(state: BackendState) -> Self {
+        match state {
+            BackendState::Running | BackendState::External => TrayState::Recording,
+            BackendState::Restarting | BackendState::Failed => TrayState::Error,
+            BackendState::Starting | BackendState::Stopped => TrayState::Idle,
+        }
+    }
+
+    fn tooltip(self) -> &'static str {
+        match self {
+            TrayState::Idle => "Earshot - idle",
+            TrayState::Recording => "Earshot - transcribing",
+            TrayState::Error => "Earshot - backend error",
+        }
+    }
+}
+
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, "open", "Open Earshot", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "start", "Start transcription", true, None::<&str>)?,
+            &MenuItem::with_id(app, "stop", "Stop transcription", true, None::<&str>)?,
+            &MenuItem::with_id(app, "pause", "Pause", true, None::<&str>)?,
+            &MenuItem::with_id(app, "resume", "Resume", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?,
+        ],
+    )?;
+
+    let base_icon = app
+        .default_window_icon()
+        .cloned()
+        .expect("bundle has no default icon")
+        .to_owned();
+
+    let tray = TrayIconBuilder::with_id("main")
+        .icon(base_icon.clone())
+        .tooltip(TrayState::Idle.tooltip())
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "open" => show_main_window(app),
+            // The rest can block for seconds (stopping waits for the backend to exit), so it
+            // runs off the main thread.
+            "start" => {
+                let app = app.clone();
+                thread::spawn(move || {
+                    let args = power::backend_args(&app, &app.state::<SettingsStore>().get());
+                    app.state::<ServerManager>().start(args);
+                });
+            }
+            "stop" => {
+                let manager = app.state::<ServerManager>().inner().clone();
+                thread::spawn(move || manager.stop());
+            }
+            "pause" => set_paused(true),
+            "resume" => set_paused(false),
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .build(app)?;
+
+    let app = app.clone();
+    thread::spawn(move || follow_backend(&app, &tray, &base_icon));
+
+    Ok(())
+}
+
+fn set_paused(paused: bool) {
+    thread::spawn(move || {
+        if let Err(e) = server_manager::set_paused(paused) {
+            tracing::warn!(
+                "could not {} transcription: {e}",
+                if paused { "pause" } else { "resume" }
+            );
+        }
+    });
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Keep the tray icon and tooltip in sync with the backend state.
+fn follow_backend(app: &AppHandle, tray: &TrayIcon, base_icon: &Image<'static>) {
+    let mut shown = TrayState::Idle;
+    loop {
+        thread::sleep(REFRESH_INTERVAL);
+
+        let state = TrayState::from_backend(app.state::<ServerManager>().state());
+        if state == shown {
+            continue;
+        }
+        shown = state;
+
+        let icon = match state {
+            TrayState::Idle => base_icon.clone(),
+            TrayState::Recording => with_badge(base_icon, RECORDING_BADGE),
+            TrayState::Error => with_badge(base_icon, ERROR_BADGE),
+        };
+        let _ = tray.set_icon(Some(icon));
+        let _ = tray.set_tooltip(Some(state.tooltip()));
+    }
+}
+
+/// Paint a filled circle in the bottom-right quarter of `icon`.
+fn with_badge(icon: &Image<'_>, [r, g, b]: [u8; 3]) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+
+    let radius = width.min(height) as i64 / 4;
+    let (cx, cy) = (width as i64 - radius, height as i64 - radius);
+    for y in (cy - radius).max(0)..height as i64 {
+        for x in (cx - radius).max(0)..width as i64 {
+            if (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2) {
+                let i = ((y * width as i64 + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[r, g, b, 0xff]);
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
@@ -0,0 +1,91 @@
+//! Text-to-speech readback.
+//!
+//! Speaks text with the platform's own synthesizer - System.Speech through PowerShell on
+//! Windows, `say` on macOS, `espeak-ng` elsewhere - so dictation can be proofread by ear. The
+//! text goes in on stdin, never on the command line. Only one utterance plays at a time.
+
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+
+use tauri::State;
+
+#[derive(Default)]
+pub struct Speech {
+    child: Mutex<Option<Child>>,
+}
+
+impl Speech {
+    fn stop_locked(child: &mut Option<Child>) {
+        if let Some(mut child) = child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn synthesizer() -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+             Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("say");
+        command.args(["-f", "-"]);
+        command
+    } else {
+        let mut command = Command::new("espeak-ng");
+        command.arg("--stdin");
+        command
+    }
+}
+
+/// Read `text` aloud, interrupting anything still playing.
+#[tauri::command]
+pub fn speak_selection(speech: State<'_, Speech>, text: String) -> Result<(), String> {
+    let mut child = speech.child.lock().unwrap();
+    Speech::stop_locked(&mut child);
+
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+
+    let mut spawned = synthesizer()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("text-to-speech unavailable: {e}"))?;
+    // Feed the text from a thread: a long text fills the pipe and the write blocks until the
+    // synthesizer catches up. Dropping stdin then tells it the text is complete.
+    if let Some(mut stdin) = spawned.stdin.take() {
+        thread::spawn(move || {
+            if let Err(e) = stdin.write_all(text.as_bytes()) {
+                // Expected when the utterance is interrupted.
+                tracing::debug!("text-to-speech input cut short: {e}");
+            }
+        });
+    }
+    *child = Some(spawned);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_speaking(speech: State<'_, Speech>) {
+    Speech::stop_locked(&mut speech.child.lock().unwrap());
+}
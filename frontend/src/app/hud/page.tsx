@@ -1,6 +1,7 @@
 'use client';
 
 import { useState, useEffect, useRef } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { useAdvisorStream } from '../../hooks/useAdvisorStream';
 
 interface TranscriptWord {
@@ -54,6 +55,23 @@ export default function HUD() {
     return () => document.removeEventListener('keydown', handleKeyDown);
   }, [isPaused, sendPause, sendResume]);
 
+  // Global push-to-talk shortcut (registered by the Tauri side, works without focus)
+  useEffect(() => {
+    const unlisten = listen<{ active: boolean }>('push-to-talk', (event) => {
+      if (event.payload.active) {
+        sendResume();
+        console.log('HUD: Push-to-talk pressed, resuming');
+      } else {
+        sendPause();
+        console.log('HUD: Push-to-talk released, pausing');
+      }
+    });
+
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, [sendPause, sendResume]);
+
   useEffect(() => {
     // Auto-fade old words
     const fadeInterval = setInterval(() => {